@@ -23,6 +23,10 @@ impl Chromosome {
         return self.genes.len();
     }
 
+    pub fn is_empty(&self) -> bool {
+        return self.genes.is_empty();
+    }
+
     pub fn iter(&self) -> impl Iterator<Item=&f32> {
         return self.genes.iter();
     }
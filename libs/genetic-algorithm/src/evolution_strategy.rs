@@ -0,0 +1,31 @@
+use rand::RngCore;
+
+pub use self::{
+    cma_es::CmaEs,
+    mu_lambda::{EsSelection, MuLambdaEs},
+};
+
+use crate::Chromosome;
+
+mod cma_es;
+mod mu_lambda;
+
+/// An optimiser driven through the ask/tell interface: it proposes a batch of candidate
+/// chromosomes (`ask()`), the caller evaluates them however it likes and reports the fitness
+/// of each candidate back (`tell()`).
+///
+/// Just like with the `GeneticAlgorithm`, higher fitness is better.
+pub trait AskTell {
+    /// Proposes the next batch of candidates to evaluate
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Chromosome>;
+
+    /// Reports fitness of the candidates returned by the latest `ask()`, in the same order
+    fn tell(&mut self, fitnesses: &[f32]);
+}
+
+/// Indices of `fitnesses`, starting from the fittest one
+pub(crate) fn rank(fitnesses: &[f32]) -> Vec<usize> {
+    let mut order: Vec<_> = (0..fitnesses.len()).collect();
+    order.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+    return order;
+}
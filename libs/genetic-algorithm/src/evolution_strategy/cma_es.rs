@@ -0,0 +1,230 @@
+use nalgebra as na;
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
+
+use crate::{AskTell, Chromosome};
+use crate::evolution_strategy::rank;
+
+/// Covariance Matrix Adaptation Evolution Strategy.
+///
+/// Samples candidates from a multivariate normal distribution and, after each `tell()`, moves
+/// the distribution's mean towards the fittest candidates while learning the shape
+/// (covariance) and size (step-size σ) of the distribution from the successful steps. Works
+/// best for fixed-size chromosomes of up to a few hundred genes.
+///
+/// Follows N. Hansen, "The CMA Evolution Strategy: A Tutorial" (2016); computations are done
+/// in `f64`.
+#[derive(Clone, Debug)]
+pub struct CmaEs {
+    lambda: usize,
+    weights: Vec<f64>,
+    mu_eff: f64,
+
+    c_sigma: f64,
+    d_sigma: f64,
+    c_c: f64,
+    c_1: f64,
+    c_mu: f64,
+    chi_n: f64,
+
+    mean: na::DVector<f64>,
+    sigma: f64,
+    cov: na::DMatrix<f64>,
+    p_sigma: na::DVector<f64>,
+    p_c: na::DVector<f64>,
+
+    /// Eigenvectors of `cov`
+    b: na::DMatrix<f64>,
+    /// Square roots of eigenvalues of `cov`
+    d: na::DVector<f64>,
+
+    generation: usize,
+
+    /// Steps (`(x - mean) / sigma`) of the candidates returned by the latest `ask()`
+    steps: Vec<na::DVector<f64>>,
+}
+
+impl CmaEs {
+    /// Creates the optimiser with the initial distribution centered at `mean`, with step-size
+    /// `sigma`, asking for `lambda` candidates at a time
+    pub fn new(mean: Chromosome, sigma: f32, lambda: usize) -> Self {
+        assert!(!mean.is_empty());
+        assert!(sigma > 0.0);
+        assert!(lambda >= 2);
+
+        let n = mean.len() as f64;
+        let mu = lambda / 2;
+
+        let weights: Vec<_> = (1..=mu)
+            .map(|i| ((mu as f64) + 0.5).ln() - (i as f64).ln())
+            .collect();
+        let weights_sum: f64 = weights.iter().sum();
+        let weights: Vec<_> = weights.into_iter().map(|w| w / weights_sum).collect();
+        let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let c_sigma = (mu_eff + 2.0) / (n + mu_eff + 5.0);
+        let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let c_c = (4.0 + mu_eff / n) / (n + 4.0 + 2.0 * mu_eff / n);
+        let c_1 = 2.0 / ((n + 1.3).powi(2) + mu_eff);
+        let c_mu = (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((n + 2.0).powi(2) + mu_eff));
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+
+        let dim = mean.len();
+
+        return Self {
+            lambda,
+            weights,
+            mu_eff,
+            c_sigma,
+            d_sigma,
+            c_c,
+            c_1,
+            c_mu,
+            chi_n,
+            mean: na::DVector::from_iterator(dim, mean.into_iter().map(|gene| gene as f64)),
+            sigma: sigma as f64,
+            cov: na::DMatrix::identity(dim, dim),
+            p_sigma: na::DVector::zeros(dim),
+            p_c: na::DVector::zeros(dim),
+            b: na::DMatrix::identity(dim, dim),
+            d: na::DVector::from_element(dim, 1.0),
+            generation: 0,
+            steps: Vec::new(),
+        };
+    }
+
+    /// Current mean of the distribution, i.e. the best guess so far
+    pub fn mean(&self) -> Chromosome {
+        return self.mean.iter().map(|&gene| gene as f32).collect();
+    }
+
+    /// Current step-size
+    pub fn sigma(&self) -> f32 {
+        return self.sigma as f32;
+    }
+
+    fn update_eigen(&mut self) {
+        // Enforce symmetry, so that rounding errors don't accumulate
+        let cov = (&self.cov + self.cov.transpose()) * 0.5;
+        let eigen = na::SymmetricEigen::new(cov.clone());
+
+        self.cov = cov;
+        self.b = eigen.eigenvectors;
+        self.d = eigen.eigenvalues.map(|value| value.max(f64::EPSILON).sqrt());
+    }
+}
+
+impl AskTell for CmaEs {
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Chromosome> {
+        let dim = self.mean.len();
+
+        self.steps = (0..self.lambda)
+            .map(|_| {
+                let z = na::DVector::from_fn(dim, |_, _| rng.sample::<f64, _>(StandardNormal));
+                return &self.b * z.component_mul(&self.d);
+            })
+            .collect();
+
+        return self.steps
+            .iter()
+            .map(|step| {
+                let x = &self.mean + step * self.sigma;
+                return x.iter().map(|&gene| gene as f32).collect();
+            })
+            .collect();
+    }
+
+    fn tell(&mut self, fitnesses: &[f32]) {
+        assert_eq!(fitnesses.len(), self.steps.len(), "got fitness for a different number of candidates than asked");
+
+        let n = self.mean.len() as f64;
+        let ranking = rank(fitnesses);
+        let dim = self.mean.len();
+
+        // Weighted recombination of the best steps
+        let mut step_w = na::DVector::zeros(dim);
+        for (weight, &idx) in self.weights.iter().zip(&ranking) {
+            step_w += &self.steps[idx] * *weight;
+        }
+
+        self.mean += &step_w * self.sigma;
+
+        // Step-size path, using C^-1/2 = B D^-1 B^T
+        let inv_sqrt_step = &self.b * (self.b.transpose() * &step_w).component_div(&self.d);
+        self.p_sigma = &self.p_sigma * (1.0 - self.c_sigma)
+            + inv_sqrt_step * (self.c_sigma * (2.0 - self.c_sigma) * self.mu_eff).sqrt();
+
+        self.generation += 1;
+
+        let p_sigma_norm = self.p_sigma.norm();
+        let h_sigma = p_sigma_norm / (1.0 - (1.0 - self.c_sigma).powi(2 * self.generation as i32)).sqrt()
+            < (1.4 + 2.0 / (n + 1.0)) * self.chi_n;
+        let h_sigma = if h_sigma { 1.0 } else { 0.0 };
+
+        // Covariance path
+        self.p_c = &self.p_c * (1.0 - self.c_c)
+            + &step_w * (h_sigma * (self.c_c * (2.0 - self.c_c) * self.mu_eff).sqrt());
+
+        // Covariance matrix: rank-one and rank-mu updates
+        let mut rank_mu = na::DMatrix::zeros(dim, dim);
+        for (weight, &idx) in self.weights.iter().zip(&ranking) {
+            let step = &self.steps[idx];
+            rank_mu += step * step.transpose() * *weight;
+        }
+
+        let rank_one = &self.p_c * self.p_c.transpose()
+            + &self.cov * ((1.0 - h_sigma) * self.c_c * (2.0 - self.c_c));
+
+        self.cov = &self.cov * (1.0 - self.c_1 - self.c_mu)
+            + rank_one * self.c_1
+            + rank_mu * self.c_mu;
+
+        // Step-size
+        self.sigma *= ((self.c_sigma / self.d_sigma) * (p_sigma_norm / self.chi_n - 1.0)).exp();
+
+        self.update_eigen();
+        self.steps.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Negated, badly scaled ellipsoid centered at (1.0, 2.0, 3.0, 4.0)
+    fn fitness(chromosome: &Chromosome) -> f32 {
+        return -chromosome
+            .iter()
+            .zip([1.0, 2.0, 3.0, 4.0])
+            .enumerate()
+            .map(|(i, (gene, target))| 10f32.powi(i as i32) * (gene - target).powi(2))
+            .sum::<f32>();
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut es = CmaEs::new(vec![0.0; 4].into_iter().collect(), 0.5, 8);
+
+        for _ in 0..200 {
+            let candidates = es.ask(&mut rng);
+            assert_eq!(candidates.len(), 8);
+
+            let fitnesses: Vec<_> = candidates.iter().map(fitness).collect();
+            es.tell(&fitnesses);
+        }
+
+        let mean: Vec<_> = es.mean().into_iter().collect();
+        approx::assert_relative_eq!(mean.as_slice(), [1.0, 2.0, 3.0, 4.0].as_ref(), epsilon = 1e-3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tell_without_ask() {
+        let mut es = CmaEs::new(vec![0.0; 4].into_iter().collect(), 0.5, 8);
+        es.tell(&[0.0; 8]);
+    }
+}
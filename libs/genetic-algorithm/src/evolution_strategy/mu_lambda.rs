@@ -0,0 +1,174 @@
+use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
+use rand_distr::StandardNormal;
+
+use crate::{AskTell, Chromosome};
+use crate::evolution_strategy::rank;
+
+/// Which candidates the next parents are chosen from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EsSelection {
+    /// (μ,λ): only from the offspring - parents always die
+    Comma,
+
+    /// (μ+λ): from both the parents and the offspring - good parents can live forever
+    Plus,
+}
+
+/// The classic (μ,λ) / (μ+λ) evolution strategy: each of the λ offspring is a copy of a random
+/// parent with gaussian noise (of standard deviation σ) added to every gene, and the μ best
+/// candidates become the next parents.
+#[derive(Clone, Debug)]
+pub struct MuLambdaEs {
+    mu: usize,
+    lambda: usize,
+    sigma: f32,
+    selection: EsSelection,
+
+    /// Current parents, together with their fitness
+    parents: Vec<(Chromosome, f32)>,
+
+    /// Offspring returned by the latest `ask()`
+    offspring: Vec<Chromosome>,
+}
+
+impl MuLambdaEs {
+    /// Creates the strategy out of initial parents (μ = `parents.len()`)
+    pub fn new(
+        parents: Vec<Chromosome>,
+        lambda: usize,
+        sigma: f32,
+        selection: EsSelection,
+    ) -> Self {
+        assert!(!parents.is_empty());
+        assert!(lambda > 0);
+        assert!(sigma > 0.0);
+
+        if selection == EsSelection::Comma {
+            assert!(lambda >= parents.len(), "(μ,λ) requires λ >= μ");
+        }
+
+        return Self {
+            mu: parents.len(),
+            lambda,
+            sigma,
+            selection,
+            // Initial parents haven't been evaluated yet, so they mustn't win over any offspring
+            parents: parents
+                .into_iter()
+                .map(|parent| (parent, f32::NEG_INFINITY))
+                .collect(),
+            offspring: Vec::new(),
+        };
+    }
+
+    /// Current parents, fittest first (in the order they were given before the first `tell()`)
+    pub fn parents(&self) -> impl Iterator<Item=&Chromosome> {
+        return self.parents.iter().map(|(parent, _)| parent);
+    }
+}
+
+impl AskTell for MuLambdaEs {
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Chromosome> {
+        self.offspring = (0..self.lambda)
+            .map(|_| {
+                let (parent, _) = self.parents.choose(rng).unwrap();
+
+                return parent
+                    .iter()
+                    .map(|gene| gene + self.sigma * rng.sample::<f32, _>(StandardNormal))
+                    .collect();
+            })
+            .collect();
+
+        return self.offspring.clone();
+    }
+
+    fn tell(&mut self, fitnesses: &[f32]) {
+        assert_eq!(fitnesses.len(), self.offspring.len(), "got fitness for a different number of candidates than asked");
+
+        let mut candidates: Vec<_> = std::mem::take(&mut self.offspring)
+            .into_iter()
+            .zip(fitnesses.iter().cloned())
+            .collect();
+
+        if self.selection == EsSelection::Plus {
+            candidates.append(&mut self.parents);
+        }
+
+        let fitnesses: Vec<_> = candidates.iter().map(|(_, fitness)| *fitness).collect();
+        let mut candidates: Vec<_> = candidates.into_iter().map(Some).collect();
+
+        self.parents = rank(&fitnesses)
+            .into_iter()
+            .take(self.mu)
+            .map(|idx| candidates[idx].take().unwrap())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Negated squared distance from (1.0, 2.0, 3.0)
+    fn fitness(chromosome: &Chromosome) -> f32 {
+        return -chromosome
+            .iter()
+            .zip([1.0, 2.0, 3.0])
+            .map(|(gene, target)| (gene - target).powi(2))
+            .sum::<f32>();
+    }
+
+    fn optimize(selection: EsSelection) -> f32 {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parents = vec![vec![0.0; 3].into_iter().collect(); 3];
+        let mut es = MuLambdaEs::new(parents, 12, 0.1, selection);
+
+        for _ in 0..100 {
+            let candidates = es.ask(&mut rng);
+            assert_eq!(candidates.len(), 12);
+
+            let fitnesses: Vec<_> = candidates.iter().map(fitness).collect();
+            es.tell(&fitnesses);
+        }
+
+        return fitness(es.parents().next().unwrap());
+    }
+
+    #[test]
+    fn comma() {
+        assert!(optimize(EsSelection::Comma) > -0.1);
+    }
+
+    #[test]
+    fn plus() {
+        assert!(optimize(EsSelection::Plus) > -0.1);
+    }
+
+    #[test]
+    fn unevaluated_parents_are_replaced() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parents = vec![vec![1.0, 2.0, 3.0].into_iter().collect()];
+        let mut es = MuLambdaEs::new(parents, 4, 0.1, EsSelection::Plus);
+
+        es.ask(&mut rng);
+        es.tell(&[-1.0; 4]);
+        es.ask(&mut rng);
+        es.tell(&[-1.0; 4]);
+
+        // The initial parent was never evaluated, so it gets replaced in the first round
+        assert_eq!(es.parents().count(), 1);
+        assert!(fitness(es.parents().next().unwrap()) < 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_offspring() {
+        let parents = vec![vec![0.0; 3].into_iter().collect()];
+        MuLambdaEs::new(parents, 0, 0.1, EsSelection::Plus);
+    }
+}
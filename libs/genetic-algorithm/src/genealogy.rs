@@ -0,0 +1,407 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Write;
+
+/// Stable identifier of an individual tracked by a `Genealogy`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct IndividualId(u64);
+
+impl IndividualId {
+    pub fn get(&self) -> u64 {
+        return self.0;
+    }
+}
+
+impl fmt::Display for IndividualId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "#{}", self.0);
+    }
+}
+
+/// Everything known about how a single individual came to be
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineageRecord {
+    pub id: IndividualId,
+
+    /// 0 for the founders, otherwise one more than the younger of the parents
+    pub generation: usize,
+
+    /// `None` for the founders (individuals that weren't bred by the algorithm)
+    pub parents: Option<[IndividualId; 2]>,
+
+    /// Crossover: how many genes were inherited from the first parent (the rest came from the
+    /// second one)
+    pub genes_from_first_parent: usize,
+
+    /// Mutation: how many genes were changed after the crossover
+    pub mutated_genes: usize,
+}
+
+/// What happened while breeding a single child, as reported by the `GeneticAlgorithm`
+#[derive(Clone, Debug)]
+pub(crate) struct Breeding {
+    pub(crate) parents: [usize; 2],
+    pub(crate) genes_from_first_parent: usize,
+    pub(crate) mutated_genes: usize,
+}
+
+/// Family tree of a population evolved via `GeneticAlgorithm::evolve_tracked()` or
+/// `GeneticAlgorithm::evolve_steady_state_tracked()`.
+///
+/// Besides all the records, the genealogy remembers which individual sits at which index of the
+/// current population - that's how the algorithm knows the ids of the parents it selects.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Genealogy {
+    /// Indexed by `IndividualId`
+    records: Vec<LineageRecord>,
+
+    /// Ids of the current population, in population order
+    current: Vec<IndividualId>,
+}
+
+impl Genealogy {
+    /// Creates a genealogy for an initial population of `founders` individuals
+    pub fn new(founders: usize) -> Self {
+        let mut this = Self::default();
+        this.record_founders(founders);
+        return this;
+    }
+
+    /// Records `count` new founders (individuals that weren't bred by a tracked algorithm, e.g.
+    /// ones that came from `MapElites`) and makes them the new current population
+    pub fn record_founders(&mut self, count: usize) {
+        self.current = (0..count)
+            .map(|_| self.push(0, None, 0, 0))
+            .collect();
+    }
+
+    /// Records `count` new founders and appends them to the current population, e.g. when an
+    /// open-ended simulation runs low on individuals
+    pub fn record_immigrants(&mut self, count: usize) {
+        for _ in 0..count {
+            let id = self.push(0, None, 0, 0);
+            self.current.push(id);
+        }
+    }
+
+    /// Records a child bred outside of a `GeneticAlgorithm` (e.g. born in an open-ended
+    /// simulation) and appends it to the current population.
+    ///
+    /// `parents` are indices into the current population.
+    pub fn record_child(
+        &mut self,
+        parents: [usize; 2],
+        genes_from_first_parent: usize,
+        mutated_genes: usize,
+    ) -> IndividualId {
+        let id = self.record_birth(Breeding { parents, genes_from_first_parent, mutated_genes });
+        self.current.push(id);
+        return id;
+    }
+
+    /// Removes the individuals at given indices of the current population (e.g. ones that
+    /// died), keeping the order of the remaining ones; their records are kept
+    pub fn record_deaths(&mut self, dead: &[usize]) {
+        let mut idx = 0;
+
+        self.current.retain(|_| {
+            idx += 1;
+            return !dead.contains(&(idx - 1));
+        });
+    }
+
+    /// Ids of the current population, in population order
+    pub fn current(&self) -> &[IndividualId] {
+        return &self.current;
+    }
+
+    pub fn get(&self, id: IndividualId) -> Option<&LineageRecord> {
+        return self.records.get(id.0 as usize);
+    }
+
+    pub fn records(&self) -> impl Iterator<Item=&LineageRecord> {
+        return self.records.iter();
+    }
+
+    /// Number of individuals recorded so far
+    pub fn len(&self) -> usize {
+        return self.records.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.records.is_empty();
+    }
+
+    /// Returns the genealogy as it was back when it had `len` records and `current` was the
+    /// current population - records are only ever appended, so that's all it takes to go back
+    /// in time
+    pub fn rewound(&self, len: usize, current: &[IndividualId]) -> Self {
+        assert!(len <= self.records.len());
+        assert!(current.iter().all(|id| (id.0 as usize) < len), "got an id from the future");
+
+        return Self {
+            records: self.records[..len].to_vec(),
+            current: current.to_vec(),
+        };
+    }
+
+    /// Returns the record of `id` followed by records of all of its ancestors, youngest first
+    pub fn ancestry(&self, id: IndividualId) -> Vec<&LineageRecord> {
+        let mut pending: BTreeSet<_> = self.get(id).map(|record| record.id).into_iter().collect();
+        let mut ancestry = Vec::new();
+
+        // Ids are handed out in order of birth, so walking them from the highest one guarantees
+        // that every child is visited before its parents
+        while let Some(id) = pending.pop_last() {
+            let record = &self.records[id.0 as usize];
+
+            if let Some(parents) = record.parents {
+                pending.extend(parents);
+            }
+
+            ancestry.push(record);
+        }
+
+        return ancestry;
+    }
+
+    /// Renders the whole genealogy as a GraphViz graph, with edges pointing from parents to
+    /// their children
+    pub fn to_dot(&self) -> String {
+        return Self::render_dot(self.records.iter());
+    }
+
+    /// Renders the ancestry of `id` (see `ancestry()`) as a GraphViz graph
+    pub fn ancestry_to_dot(&self, id: IndividualId) -> String {
+        return Self::render_dot(self.ancestry(id).into_iter().rev());
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).expect("genealogy is always serializable");
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        return serde_json::from_str(json);
+    }
+
+    /// Records children bred out of the current population and makes them the new current
+    /// population (see `GeneticAlgorithm::evolve_tracked()`)
+    pub(crate) fn record_generation(&mut self, breedings: Vec<Breeding>) {
+        self.current = breedings
+            .into_iter()
+            .map(|breeding| self.record_birth(breeding))
+            .collect();
+    }
+
+    /// Records children bred out of the current population and puts them in place of the
+    /// victims, mirroring `apply_replacements()`
+    pub(crate) fn record_replacements(&mut self, breedings: Vec<(usize, Breeding)>) {
+        let replacements = breedings
+            .into_iter()
+            .map(|(victim, breeding)| (victim, self.record_birth(breeding)))
+            .collect();
+
+        let mut current = std::mem::take(&mut self.current);
+        crate::apply_replacements(&mut current, replacements);
+        self.current = current;
+    }
+
+    fn record_birth(&mut self, breeding: Breeding) -> IndividualId {
+        let parents = breeding.parents.map(|idx| self.current[idx]);
+
+        let generation = parents
+            .iter()
+            .map(|&parent| self.records[parent.0 as usize].generation)
+            .max()
+            .unwrap() + 1;
+
+        return self.push(
+            generation,
+            Some(parents),
+            breeding.genes_from_first_parent,
+            breeding.mutated_genes,
+        );
+    }
+
+    fn push(
+        &mut self,
+        generation: usize,
+        parents: Option<[IndividualId; 2]>,
+        genes_from_first_parent: usize,
+        mutated_genes: usize,
+    ) -> IndividualId {
+        let id = IndividualId(self.records.len() as u64);
+
+        self.records.push(LineageRecord {
+            id,
+            generation,
+            parents,
+            genes_from_first_parent,
+            mutated_genes,
+        });
+
+        return id;
+    }
+
+    fn render_dot<'a>(records: impl Iterator<Item=&'a LineageRecord>) -> String {
+        let mut dot = String::from("digraph genealogy {\n");
+
+        for record in records {
+            writeln!(
+                dot,
+                "    {} [label=\"{}\\ngen {}\\nmut {}\"];",
+                record.id.0, record.id, record.generation, record.mutated_genes,
+            ).unwrap();
+
+            if let Some([parent_a, parent_b]) = record.parents {
+                writeln!(dot, "    {} -> {};", parent_a.0, record.id.0).unwrap();
+
+                if parent_b != parent_a {
+                    writeln!(dot, "    {} -> {};", parent_b.0, record.id.0).unwrap();
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        return dot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breeding(parents: [usize; 2]) -> Breeding {
+        return Breeding { parents, genes_from_first_parent: 2, mutated_genes: 1 };
+    }
+
+    /// Founders #0, #1, #2; then #3 = #0 x #1, #4 = #2 x #2, #5 = #1 x #2; then #6 = #3 x #5
+    fn genealogy() -> Genealogy {
+        let mut genealogy = Genealogy::new(3);
+        genealogy.record_generation(vec![breeding([0, 1]), breeding([2, 2]), breeding([1, 2])]);
+        genealogy.record_generation(vec![breeding([0, 2])]);
+        return genealogy;
+    }
+
+    fn ids(records: Vec<&LineageRecord>) -> Vec<u64> {
+        return records.into_iter().map(|record| record.id.get()).collect();
+    }
+
+    mod record_generation {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let genealogy = genealogy();
+            let record = genealogy.get(IndividualId(6)).unwrap();
+
+            assert_eq!(genealogy.current(), &[IndividualId(6)]);
+            assert_eq!(record.parents, Some([IndividualId(3), IndividualId(5)]));
+            assert_eq!(record.generation, 2);
+            assert_eq!(record.genes_from_first_parent, 2);
+            assert_eq!(record.mutated_genes, 1);
+        }
+    }
+
+    mod record_replacements {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut genealogy = Genealogy::new(3);
+            genealogy.record_replacements(vec![(0, breeding([1, 2]))]);
+
+            assert_eq!(genealogy.current(), &[IndividualId(1), IndividualId(2), IndividualId(3)]);
+            assert_eq!(genealogy.get(IndividualId(3)).unwrap().generation, 1);
+        }
+    }
+
+    mod open_ended {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut genealogy = Genealogy::new(3);
+
+            assert_eq!(genealogy.record_child([1, 2], 2, 1), IndividualId(3));
+            genealogy.record_deaths(&[0, 2]);
+            genealogy.record_immigrants(1);
+
+            assert_eq!(genealogy.current(), &[IndividualId(1), IndividualId(3), IndividualId(4)]);
+            assert_eq!(genealogy.get(IndividualId(3)).unwrap().parents, Some([IndividualId(1), IndividualId(2)]));
+            assert_eq!(genealogy.get(IndividualId(4)).unwrap().parents, None);
+        }
+    }
+
+    mod rewound {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let past = Genealogy::new(3);
+            let genealogy = genealogy().rewound(past.len(), past.current());
+
+            assert_eq!(genealogy.records, past.records);
+            assert_eq!(genealogy.current, past.current);
+        }
+
+        #[test]
+        #[should_panic(expected = "got an id from the future")]
+        fn from_the_future() {
+            genealogy().rewound(3, &[IndividualId(6)]);
+        }
+    }
+
+    mod ancestry {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let genealogy = genealogy();
+
+            assert_eq!(ids(genealogy.ancestry(IndividualId(6))), vec![6, 5, 3, 2, 1, 0]);
+            assert_eq!(ids(genealogy.ancestry(IndividualId(4))), vec![4, 2]);
+            assert_eq!(ids(genealogy.ancestry(IndividualId(0))), vec![0]);
+            assert_eq!(ids(genealogy.ancestry(IndividualId(100))), Vec::<u64>::new());
+        }
+    }
+
+    mod to_dot {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let actual = genealogy().ancestry_to_dot(IndividualId(4));
+
+            let expected = "digraph genealogy {\n    \
+                2 [label=\"#2\\ngen 0\\nmut 0\"];\n    \
+                4 [label=\"#4\\ngen 1\\nmut 1\"];\n    \
+                2 -> 4;\n\
+            }\n";
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod to_json {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let genealogy = genealogy();
+            let json = genealogy.to_json();
+
+            assert!(json.contains("\"current\":[6]"));
+
+            let restored = Genealogy::from_json(&json).unwrap();
+            assert_eq!(restored.records, genealogy.records);
+            assert_eq!(restored.current, genealogy.current);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#![feature(type_alias_impl_trait)]
+#![feature(impl_trait_in_assoc_type)]
 
 extern crate core;
 
@@ -6,25 +6,49 @@ use rand::RngCore;
 
 pub use self::{
     chromosome::Chromosome,
-    crossover::UniformCrossover,
+    crossover::{CrossoverMethod, UniformCrossover},
+    evolution_strategy::{AskTell, CmaEs, EsSelection, MuLambdaEs},
+    genealogy::{Genealogy, IndividualId, LineageRecord},
     individual::Individual,
-    mutation::GaussianMutation,
-    selection::RouletteWheelSelection,
+    map_elites::{Elite, GridAxis, MapElites},
+    mutation::{GaussianMutation, MutationMethod},
+    novelty::{BehavioralIndividual, NoveltyFitness, NoveltySearch},
+    optimizer::{AskTellOptimizer, Optimizer},
+    replacement::{
+        CrowdingReplacement,
+        ReplaceOldest,
+        ReplaceRandom,
+        ReplacementMethod,
+        ReplaceWorst,
+        TournamentReplacement,
+    },
+    scaling::{
+        BoltzmannScaling,
+        FitnessScaling,
+        LinearScaling,
+        OffsetScaling,
+        RankScaling,
+        SigmaTruncation,
+    },
+    selection::{RouletteWheelSelection, ScaledSelection, SelectionMethod},
     statistics::Statistics,
 };
 
-use self::{
-    crossover::*,
-    mutation::*,
-    selection::*,
-};
+use self::genealogy::Breeding;
 #[cfg(test)]
 use self::individual::TestIndividual;
 
 mod chromosome;
 mod crossover;
+mod evolution_strategy;
+mod genealogy;
 mod individual;
+mod map_elites;
 mod mutation;
+mod novelty;
+mod optimizer;
+mod replacement;
+mod scaling;
 mod selection;
 mod statistics;
 
@@ -59,22 +83,167 @@ impl<S> GeneticAlgorithm<S>
     {
         assert!(!population.is_empty());
         let new_population = (0..population.len())
-            .map(|_| {
-                // Selection
-                let [parent_a, parent_b] = [0, 1].map(|_| self.selection_method.select(rng, population).chromosome());
-
-                // Crossover genes
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+            .map(|_| self.breed(rng, population))
+            .collect();
+        let stats = Statistics::new(population);
+        return (new_population, stats);
+    }
 
-                // Mutate genes
-                self.mutation_method.mutate(rng, &mut child);
+    /// Steady-state counterpart of `evolve()`: instead of replacing the whole population at
+    /// once, breeds just `offspring` children and lets `replacement_method` decide whom they
+    /// replace.
+    ///
+    /// Returns each child together with the index (into `population`) of the individual it
+    /// replaces. Callers that keep their population ordered from the oldest to the youngest
+    /// (which e.g. `ReplaceOldest` relies on) should remove the victims and append the children
+    /// at the end - see `apply_replacements()`.
+    pub fn evolve_steady_state<I, R>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        offspring: usize,
+        replacement_method: &R,
+    ) -> (Vec<(usize, I)>, Statistics)
+        where
+            I: Individual,
+            R: ReplacementMethod,
+    {
+        assert!(!population.is_empty());
+        assert!(offspring <= population.len());
 
-                return I::create(child);
-            })
+        let children: Vec<_> = (0..offspring)
+            .map(|_| self.breed(rng, population))
             .collect();
+
+        let victims = replacement_method.select_victims(rng, population, &children);
+        assert_eq!(victims.len(), children.len());
+
+        let stats = Statistics::new(population);
+        return (victims.into_iter().zip(children).collect(), stats);
+    }
+
+    /// Same as `evolve()`, but additionally records parents, crossover and mutation of every
+    /// child in `genealogy`.
+    ///
+    /// `genealogy` must describe `population`, i.e. it should either be freshly created via
+    /// `Genealogy::new(population.len())` or have been passed to the call that produced
+    /// `population`.
+    pub fn evolve_tracked<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        genealogy: &mut Genealogy,
+    ) -> (Vec<I>, Statistics)
+        where
+            I: Individual
+    {
+        assert!(!population.is_empty());
+        assert_eq!(genealogy.current().len(), population.len());
+
+        let (new_population, breedings) = (0..population.len())
+            .map(|_| self.breed_tracked(rng, population))
+            .unzip();
+
+        genealogy.record_generation(breedings);
+
         let stats = Statistics::new(population);
         return (new_population, stats);
     }
+
+    /// Same as `evolve_steady_state()`, but additionally records parents, crossover and mutation
+    /// of every child in `genealogy` (see `evolve_tracked()`).
+    pub fn evolve_steady_state_tracked<I, R>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        offspring: usize,
+        replacement_method: &R,
+        genealogy: &mut Genealogy,
+    ) -> (Vec<(usize, I)>, Statistics)
+        where
+            I: Individual,
+            R: ReplacementMethod,
+    {
+        assert!(!population.is_empty());
+        assert!(offspring <= population.len());
+        assert_eq!(genealogy.current().len(), population.len());
+
+        let (children, breedings): (Vec<_>, Vec<_>) = (0..offspring)
+            .map(|_| self.breed_tracked(rng, population))
+            .unzip();
+
+        let victims = replacement_method.select_victims(rng, population, &children);
+        assert_eq!(victims.len(), children.len());
+
+        genealogy.record_replacements(victims.iter().cloned().zip(breedings).collect());
+
+        let stats = Statistics::new(population);
+        return (victims.into_iter().zip(children).collect(), stats);
+    }
+
+    fn breed<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> I
+        where
+            I: Individual
+    {
+        // Selection
+        let [parent_a, parent_b] = [0, 1].map(|_| self.selection_method.select(rng, population).chromosome());
+
+        // Crossover genes
+        let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+
+        // Mutate genes
+        self.mutation_method.mutate(rng, &mut child);
+
+        return I::create(child);
+    }
+
+    fn breed_tracked<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (I, Breeding)
+        where
+            I: Individual
+    {
+        // Selection
+        let parents = [0, 1].map(|_| {
+            let parent = self.selection_method.select(rng, population);
+
+            return population
+                .iter()
+                .position(|individual| std::ptr::eq(individual, parent))
+                .expect("selection method returned an individual from outside of the population");
+        });
+
+        let [parent_a, parent_b] = parents.map(|idx| population[idx].chromosome());
+
+        // Crossover genes
+        let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        let crossed = child.clone();
+
+        // Mutate genes
+        self.mutation_method.mutate(rng, &mut child);
+
+        let breeding = Breeding {
+            parents,
+            genes_from_first_parent: crossed.iter().zip(parent_a.iter()).filter(|(c, p)| c == p).count(),
+            mutated_genes: crossed.iter().zip(child.iter()).filter(|(c, m)| c != m).count(),
+        };
+
+        return (I::create(child), breeding);
+    }
+}
+
+/// Applies the outcome of `GeneticAlgorithm::evolve_steady_state()` to `population`: victims are
+/// removed and children are appended at the end, keeping the population ordered from the
+/// oldest to the youngest.
+pub fn apply_replacements<T>(population: &mut Vec<T>, replacements: Vec<(usize, T)>) {
+    let mut victims: Vec<_> = replacements.iter().map(|(idx, _)| *idx).collect();
+    victims.sort_unstable();
+    victims.dedup();
+    assert_eq!(victims.len(), replacements.len(), "got duplicated victims");
+
+    for idx in victims.into_iter().rev() {
+        population.remove(idx);
+    }
+
+    population.extend(replacements.into_iter().map(|(_, child)| child));
 }
 
 #[cfg(test)]
@@ -124,4 +293,103 @@ mod tests {
 
         assert_eq!(population, expected_population);
     }
-}
\ No newline at end of file
+
+    mod evolve_steady_state {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let mut population = vec![
+                individual(&[0.0, 0.0, 0.0]), // fitness = 0.0
+                individual(&[1.0, 1.0, 1.0]), // fitness = 3.0
+                individual(&[1.0, 2.0, 1.0]), // fitness = 4.0
+                individual(&[1.0, 2.0, 4.0]), // fitness = 7.0
+            ];
+
+            let (replacements, _) = ga.evolve_steady_state(&mut rng, &population, 2, &ReplaceWorst::new());
+            let victims: Vec<_> = replacements.iter().map(|(idx, _)| *idx).collect();
+            assert_eq!(victims, vec![0, 1]);
+
+            apply_replacements(&mut population, replacements);
+            assert_eq!(population.len(), 4);
+            assert_eq!(population[0], individual(&[1.0, 2.0, 1.0]));
+            assert_eq!(population[1], individual(&[1.0, 2.0, 4.0]));
+
+            // Steady-state evolution should still improve the population over time
+            for _ in 0..20 {
+                let (replacements, _) = ga.evolve_steady_state(&mut rng, &population, 2, &ReplaceWorst::new());
+                apply_replacements(&mut population, replacements);
+            }
+
+            assert!(population.iter().all(|individual| individual.fitness() > 4.0));
+        }
+    }
+
+    mod evolve_tracked {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let mut population = vec![
+                individual(&[0.0, 0.0, 0.0]), // fitness = 0.0
+                individual(&[1.0, 1.0, 1.0]), // fitness = 3.0
+                individual(&[1.0, 2.0, 1.0]), // fitness = 4.0
+                individual(&[1.0, 2.0, 4.0]), // fitness = 7.0
+            ];
+            let mut genealogy = Genealogy::new(population.len());
+
+            for _ in 0..10 {
+                let (new_pop, _) = ga.evolve_tracked(&mut rng, &population, &mut genealogy);
+                population = new_pop;
+            }
+
+            // Tracking must not change the outcome of the evolution (see `tests::test`)
+            assert_eq!(population[0], individual(&[0.4476949, 2.0648358, 4.3058133]));
+
+            assert_eq!(genealogy.records().count(), 4 * 11);
+            assert_eq!(genealogy.current().len(), 4);
+
+            for &id in genealogy.current() {
+                let record = genealogy.get(id).unwrap();
+                assert_eq!(record.generation, 10);
+                assert!(record.genes_from_first_parent <= 3);
+                assert!(record.mutated_genes <= 3);
+
+                let ancestry = genealogy.ancestry(id);
+                assert_eq!(ancestry[0].id, id);
+                assert!(ancestry.iter().any(|record| record.generation == 0));
+
+                // The least fit founder (fitness = 0.0) can never be selected as a parent
+                assert!(ancestry.iter().all(|record| record.id.get() != 0));
+            }
+        }
+    }
+
+    mod apply_replacements {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut population = vec!['a', 'b', 'c', 'd'];
+            apply_replacements(&mut population, vec![(2, 'x'), (0, 'y')]);
+
+            assert_eq!(population, vec!['b', 'd', 'x', 'y']);
+        }
+    }
+}
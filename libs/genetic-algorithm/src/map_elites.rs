@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+
+use rand::RngCore;
+use rand::seq::SliceRandom;
+
+use crate::*;
+
+/// A single dimension of the behavior space searched by `MapElites`
+#[derive(Clone, Debug)]
+pub struct GridAxis {
+    min: f32,
+    max: f32,
+    cells: usize,
+}
+
+impl GridAxis {
+    /// Splits <min, max> into `cells` equal cells; values outside that range fall into the
+    /// first / last cell.
+    pub fn new(min: f32, max: f32, cells: usize) -> Self {
+        assert!(min < max);
+        assert!(cells > 0);
+
+        return Self { min, max, cells };
+    }
+
+    pub fn cells(&self) -> usize {
+        return self.cells;
+    }
+
+    fn cell(&self, value: f32) -> usize {
+        let value = (value - self.min) / (self.max - self.min);
+        let cell = (value * self.cells as f32).max(0.0) as usize;
+
+        return cell.min(self.cells - 1);
+    }
+}
+
+/// The best individual found so far within a single cell of the grid
+#[derive(Clone, Debug)]
+pub struct Elite {
+    chromosome: Chromosome,
+    fitness: f32,
+    behavior: Vec<f32>,
+}
+
+impl Elite {
+    pub fn chromosome(&self) -> &Chromosome {
+        return &self.chromosome;
+    }
+
+    pub fn fitness(&self) -> f32 {
+        return self.fitness;
+    }
+
+    pub fn behavior(&self) -> &[f32] {
+        return &self.behavior;
+    }
+}
+
+/// MAP-Elites (Multi-dimensional Archive of Phenotypic Elites): instead of converging to a
+/// single champion, splits the behavior space into a grid and keeps the best individual found
+/// in each of its cells, so that in the end there's a whole gallery of different solutions.
+///
+/// Behavior descriptors come from `BehavioralIndividual::behavior()` and must have one value per
+/// axis.
+pub struct MapElites {
+    axes: Vec<GridAxis>,
+    crossover_method: Box<dyn CrossoverMethod>,
+    mutation_method: Box<dyn MutationMethod>,
+    elites: BTreeMap<Vec<usize>, Elite>,
+}
+
+impl MapElites {
+    pub fn new(
+        axes: Vec<GridAxis>,
+        crossover_method: impl CrossoverMethod + 'static,
+        mutation_method: impl MutationMethod + 'static,
+    ) -> Self {
+        assert!(!axes.is_empty());
+
+        return Self {
+            axes,
+            crossover_method: Box::new(crossover_method),
+            mutation_method: Box::new(mutation_method),
+            elites: BTreeMap::new(),
+        };
+    }
+
+    pub fn axes(&self) -> &[GridAxis] {
+        return &self.axes;
+    }
+
+    /// Returns the elite of given cell (one index per axis), if that cell has been reached yet
+    pub fn get(&self, cell: &[usize]) -> Option<&Elite> {
+        return self.elites.get(cell);
+    }
+
+    /// Iterates over all of the reached cells, together with their elites
+    pub fn elites(&self) -> impl Iterator<Item=(&[usize], &Elite)> {
+        return self.elites
+            .iter()
+            .map(|(cell, elite)| (cell.as_slice(), elite));
+    }
+
+    /// Fraction of the grid's cells that have been reached:
+    /// - 0.0 = none
+    /// - 1.0 = all of them
+    pub fn coverage(&self) -> f32 {
+        let cells: usize = self.axes.iter().map(|axis| axis.cells).product();
+        return self.elites.len() as f32 / cells as f32;
+    }
+
+    /// Returns the cell given behavior descriptor falls into
+    pub fn cell(&self, behavior: &[f32]) -> Vec<usize> {
+        assert_eq!(behavior.len(), self.axes.len(), "behavior descriptor doesn't match the grid");
+
+        return self.axes
+            .iter()
+            .zip(behavior)
+            .map(|(axis, &value)| axis.cell(value))
+            .collect();
+    }
+
+    /// Puts `individual` into its cell if the cell is empty or the current elite is less fit.
+    ///
+    /// Returns whether the individual has become an elite.
+    pub fn insert<I>(&mut self, individual: &I) -> bool
+        where
+            I: BehavioralIndividual
+    {
+        let cell = self.cell(individual.behavior());
+        let fitness = individual.fitness();
+
+        if let Some(elite) = self.elites.get(&cell) {
+            if elite.fitness >= fitness {
+                return false;
+            }
+        }
+
+        self.elites.insert(cell, Elite {
+            chromosome: individual.chromosome().clone(),
+            fitness,
+            behavior: individual.behavior().to_vec(),
+        });
+
+        return true;
+    }
+
+    /// Inserts the whole population into the grid and then breeds a new population of the same
+    /// size, each child being a crossed-over and mutated pair of randomly chosen elites.
+    pub fn evolve<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+    ) -> (Vec<I>, Statistics)
+        where
+            I: BehavioralIndividual
+    {
+        assert!(!population.is_empty());
+
+        for individual in population {
+            self.insert(individual);
+        }
+
+        let elites: Vec<_> = self.elites.values().collect();
+
+        let new_population = (0..population.len())
+            .map(|_| {
+                let [parent_a, parent_b] = [0, 1].map(|_| elites.choose(rng).unwrap().chromosome());
+
+                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+                self.mutation_method.mutate(rng, &mut child);
+
+                return I::create(child);
+            })
+            .collect();
+
+        let stats = Statistics::new(population);
+        return (new_population, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Behaves like its two genes say and is as fit as their sum
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestIndividual {
+        chromosome: Chromosome,
+        behavior: Vec<f32>,
+    }
+
+    impl Individual for TestIndividual {
+        fn fitness(&self) -> f32 {
+            return self.chromosome.iter().sum();
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            return &self.chromosome;
+        }
+
+        fn create(chromosome: Chromosome) -> Self {
+            let behavior = chromosome.iter().take(2).cloned().collect();
+            return Self { chromosome, behavior };
+        }
+    }
+
+    impl BehavioralIndividual for TestIndividual {
+        fn behavior(&self) -> &[f32] {
+            return &self.behavior;
+        }
+    }
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        return TestIndividual::create(genes.iter().cloned().collect());
+    }
+
+    fn map_elites() -> MapElites {
+        return MapElites::new(
+            vec![GridAxis::new(0.0, 1.0, 2), GridAxis::new(0.0, 1.0, 2)],
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.1),
+        );
+    }
+
+    mod cell {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let map_elites = map_elites();
+
+            assert_eq!(map_elites.cell(&[0.0, 0.0]), vec![0, 0]);
+            assert_eq!(map_elites.cell(&[0.7, 0.2]), vec![1, 0]);
+            assert_eq!(map_elites.cell(&[1.0, 0.5]), vec![1, 1]);
+            assert_eq!(map_elites.cell(&[-5.0, 5.0]), vec![0, 1]);
+        }
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn keeps_the_best_individual_per_cell() {
+            let mut map_elites = map_elites();
+
+            assert!(map_elites.insert(&individual(&[0.1, 0.1, 0.0])));
+            assert!(map_elites.insert(&individual(&[0.2, 0.2, 0.0])));
+            assert!(!map_elites.insert(&individual(&[0.1, 0.1, 0.1])));
+            assert!(map_elites.insert(&individual(&[0.9, 0.1, 0.0])));
+
+            assert_eq!(map_elites.elites().count(), 2);
+            approx::assert_relative_eq!(map_elites.get(&[0, 0]).unwrap().fitness(), 0.4);
+            approx::assert_relative_eq!(map_elites.coverage(), 0.5);
+        }
+    }
+
+    mod evolve {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut map_elites = map_elites();
+
+            let mut population = vec![
+                individual(&[0.1, 0.1, 0.0]),
+                individual(&[0.1, 0.2, 0.0]),
+                individual(&[0.2, 0.1, 0.0]),
+                individual(&[0.2, 0.2, 0.0]),
+            ];
+
+            for _ in 0..20 {
+                let (new_population, _) = map_elites.evolve(&mut rng, &population);
+                population = new_population;
+            }
+
+            assert_eq!(population.len(), 4);
+            assert!(map_elites.coverage() > 0.5);
+        }
+    }
+}
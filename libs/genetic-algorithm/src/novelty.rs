@@ -0,0 +1,283 @@
+use crate::Individual;
+
+/// An individual that, besides its fitness, can describe _how_ it behaved, e.g. where it ended
+/// up or how fast it moved.
+///
+/// Descriptors of all individuals passed to a `NoveltySearch` must have the same length.
+pub trait BehavioralIndividual: Individual {
+    fn behavior(&self) -> &[f32];
+}
+
+/// What `NoveltySearch::evaluate()` returns as the fitness of an individual
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoveltyFitness {
+    /// Only the novelty of the behavior counts
+    Novelty,
+
+    /// Only the original fitness counts (the archive is still maintained)
+    Objective,
+
+    /// Both novelty and the original fitness are scaled to <0, 1> (relative to the best
+    /// individual in the population) and mixed:
+    /// - 0.0 = same as `Objective`
+    /// - 1.0 = same as `Novelty`
+    Weighted { novelty_weight: f32 },
+}
+
+/// Novelty search: instead of rewarding individuals for being good, rewards them for behaving
+/// differently than everybody else - both the current population and the notable individuals
+/// from the past (the archive).
+///
+/// Novelty of an individual is the average distance between its behavior descriptor and the
+/// `k` nearest descriptors.
+#[derive(Clone, Debug)]
+pub struct NoveltySearch {
+    k: usize,
+
+    /// Individuals more novel than this are remembered in the archive
+    archive_threshold: f32,
+
+    /// Maximum number of remembered behaviors; the oldest ones are forgotten first
+    archive_capacity: usize,
+
+    fitness: NoveltyFitness,
+    archive: Vec<Vec<f32>>,
+}
+
+impl NoveltySearch {
+    pub fn new(
+        k: usize,
+        archive_threshold: f32,
+        archive_capacity: usize,
+        fitness: NoveltyFitness,
+    ) -> Self {
+        assert!(k > 0);
+        assert!(archive_threshold >= 0.0);
+
+        if let NoveltyFitness::Weighted { novelty_weight } = fitness {
+            assert!((0.0..=1.0).contains(&novelty_weight));
+        }
+
+        return Self {
+            k,
+            archive_threshold,
+            archive_capacity,
+            fitness,
+            archive: Vec::new(),
+        };
+    }
+
+    pub fn archive(&self) -> &[Vec<f32>] {
+        return &self.archive;
+    }
+
+    /// Computes novelty of every individual in `population`, updates the archive and returns
+    /// fitness values (in population order) according to the configured `NoveltyFitness`.
+    pub fn evaluate<I>(&mut self, population: &[I]) -> Vec<f32>
+        where
+            I: BehavioralIndividual
+    {
+        let novelties: Vec<_> = population
+            .iter()
+            .enumerate()
+            .map(|(idx, individual)| self.novelty(idx, population, individual.behavior()))
+            .collect();
+
+        for (individual, &novelty) in population.iter().zip(&novelties) {
+            if novelty > self.archive_threshold {
+                self.archive.push(individual.behavior().to_vec());
+            }
+        }
+
+        if self.archive.len() > self.archive_capacity {
+            let overflow = self.archive.len() - self.archive_capacity;
+            self.archive.drain(..overflow);
+        }
+
+        return match self.fitness {
+            NoveltyFitness::Novelty => novelties,
+
+            NoveltyFitness::Objective => population
+                .iter()
+                .map(|individual| individual.fitness())
+                .collect(),
+
+            NoveltyFitness::Weighted { novelty_weight } => {
+                let objectives: Vec<_> = population
+                    .iter()
+                    .map(|individual| individual.fitness())
+                    .collect();
+
+                normalize(&novelties)
+                    .zip(normalize(&objectives))
+                    .map(|(novelty, objective)| {
+                        novelty_weight * novelty + (1.0 - novelty_weight) * objective
+                    })
+                    .collect()
+            }
+        };
+    }
+
+    /// Average distance between `behavior` and the `k` nearest behaviors from the population
+    /// (except for the individual itself, at `idx`) and the archive
+    fn novelty<I>(&self, idx: usize, population: &[I], behavior: &[f32]) -> f32
+        where
+            I: BehavioralIndividual
+    {
+        let others = population
+            .iter()
+            .enumerate()
+            .filter(|(other_idx, _)| *other_idx != idx)
+            .map(|(_, other)| other.behavior())
+            .chain(self.archive.iter().map(|other| other.as_slice()));
+
+        let mut distances: Vec<_> = others
+            .map(|other| distance(behavior, other))
+            .collect();
+
+        if distances.is_empty() {
+            return 0.0;
+        }
+
+        let k = self.k.min(distances.len());
+        distances.select_nth_unstable_by(k - 1, f32::total_cmp);
+
+        return distances[..k].iter().sum::<f32>() / (k as f32);
+    }
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len(), "behavior descriptors must have the same length");
+
+    return a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f32>()
+        .sqrt();
+}
+
+/// Scales non-negative values so that the largest one becomes 1.0
+fn normalize(values: &[f32]) -> impl Iterator<Item=f32> + '_ {
+    let max = values.iter().cloned().fold(0.0, f32::max);
+
+    return values
+        .iter()
+        .map(move |value| if max > 0.0 { value.max(0.0) / max } else { 0.0 });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Chromosome;
+
+    use super::*;
+
+    struct TestIndividual {
+        fitness: f32,
+        behavior: Vec<f32>,
+    }
+
+    impl Individual for TestIndividual {
+        fn fitness(&self) -> f32 {
+            return self.fitness;
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            panic!("Not supported for novelty::TestIndividual");
+        }
+
+        fn create(_: Chromosome) -> Self {
+            panic!("Not supported for novelty::TestIndividual");
+        }
+    }
+
+    impl BehavioralIndividual for TestIndividual {
+        fn behavior(&self) -> &[f32] {
+            return &self.behavior;
+        }
+    }
+
+    /// Three birds huddled together around x = 0, one loner at x = 10
+    fn population() -> Vec<TestIndividual> {
+        return vec![
+            TestIndividual { fitness: 4.0, behavior: vec![0.0] },
+            TestIndividual { fitness: 2.0, behavior: vec![1.0] },
+            TestIndividual { fitness: 3.0, behavior: vec![2.0] },
+            TestIndividual { fitness: 1.0, behavior: vec![10.0] },
+        ];
+    }
+
+    mod novelty {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut novelty = NoveltySearch::new(2, 100.0, 10, NoveltyFitness::Novelty);
+            let actual = novelty.evaluate(&population());
+            let expected = vec![1.5, 1.0, 1.5, 8.5];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    mod objective {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut novelty = NoveltySearch::new(2, 100.0, 10, NoveltyFitness::Objective);
+            let actual = novelty.evaluate(&population());
+            let expected = vec![4.0, 2.0, 3.0, 1.0];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    mod weighted {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut novelty = NoveltySearch::new(
+                2,
+                100.0,
+                10,
+                NoveltyFitness::Weighted { novelty_weight: 0.5 },
+            );
+
+            let actual = novelty.evaluate(&population());
+            let expected = vec![
+                0.5 * (1.5 / 8.5) + 0.5 * 1.0,
+                0.5 * (1.0 / 8.5) + 0.5 * 0.5,
+                0.5 * (1.5 / 8.5) + 0.5 * 0.75,
+                0.5 * 1.0 + 0.5 * 0.25,
+            ];
+
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    mod archive {
+        use super::*;
+
+        #[test]
+        fn remembers_novel_behaviors() {
+            let mut novelty = NoveltySearch::new(2, 5.0, 10, NoveltyFitness::Novelty);
+            novelty.evaluate(&population());
+
+            assert_eq!(novelty.archive(), &[vec![10.0]]);
+
+            // The loner's behavior is no longer novel once it's in the archive
+            let actual = novelty.evaluate(&population());
+            approx::assert_relative_eq!(actual[3], 4.0);
+        }
+
+        #[test]
+        fn forgets_the_oldest_behaviors() {
+            let mut novelty = NoveltySearch::new(1, 0.0, 3, NoveltyFitness::Novelty);
+            novelty.evaluate(&population());
+
+            assert_eq!(novelty.archive(), &[vec![1.0], vec![2.0], vec![10.0]]);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use rand::RngCore;
+
+use crate::*;
+
+/// Anything that can breed the next generation out of an evaluated population - the
+/// `GeneticAlgorithm`, `MapElites`, an evolution strategy (via `AskTellOptimizer`) or your own
+/// algorithm.
+pub trait Optimizer<I> {
+    /// Breeds the next generation out of `population` and returns it together with statistics
+    /// of `population`.
+    ///
+    /// `genealogy` describes `population` and must be updated so that it describes the
+    /// returned generation afterwards; optimisers that don't track parents can simply call
+    /// `genealogy.record_founders()`.
+    fn evolve(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        genealogy: &mut Genealogy,
+    ) -> (Vec<I>, Statistics);
+}
+
+impl<I, O> Optimizer<I> for Box<O>
+    where
+        O: Optimizer<I> + ?Sized
+{
+    fn evolve(&mut self, rng: &mut dyn RngCore, population: &[I], genealogy: &mut Genealogy) -> (Vec<I>, Statistics) {
+        return (**self).evolve(rng, population, genealogy);
+    }
+}
+
+impl<I, S> Optimizer<I> for GeneticAlgorithm<S>
+    where
+        I: Individual,
+        S: SelectionMethod,
+{
+    fn evolve(&mut self, rng: &mut dyn RngCore, population: &[I], genealogy: &mut Genealogy) -> (Vec<I>, Statistics) {
+        return self.evolve_tracked(rng, population, genealogy);
+    }
+}
+
+impl<I> Optimizer<I> for MapElites
+    where
+        I: BehavioralIndividual
+{
+    fn evolve(&mut self, rng: &mut dyn RngCore, population: &[I], genealogy: &mut Genealogy) -> (Vec<I>, Statistics) {
+        let (new_population, stats) = MapElites::evolve(self, rng, population);
+
+        // Children are bred out of the elites rather than out of `population`
+        genealogy.record_founders(new_population.len());
+
+        return (new_population, stats);
+    }
+}
+
+/// Adapts an ask/tell optimiser (e.g. `CmaEs`) to the `Optimizer` interface: each `evolve()`
+/// tells the strategy fitness of the given population and asks it for the next one.
+///
+/// The population passed to the very first `evolve()` wasn't proposed by the strategy, so it's
+/// only used for statistics.
+pub struct AskTellOptimizer<T> {
+    strategy: T,
+    asked: bool,
+}
+
+impl<T> AskTellOptimizer<T>
+    where
+        T: AskTell
+{
+    pub fn new(strategy: T) -> Self {
+        return Self { strategy, asked: false };
+    }
+
+    pub fn strategy(&self) -> &T {
+        return &self.strategy;
+    }
+}
+
+impl<I, T> Optimizer<I> for AskTellOptimizer<T>
+    where
+        I: Individual,
+        T: AskTell,
+{
+    fn evolve(&mut self, rng: &mut dyn RngCore, population: &[I], genealogy: &mut Genealogy) -> (Vec<I>, Statistics) {
+        assert!(!population.is_empty());
+
+        if self.asked {
+            let fitnesses: Vec<_> = population
+                .iter()
+                .map(|individual| individual.fitness())
+                .collect();
+
+            self.strategy.tell(&fitnesses);
+        }
+
+        let new_population: Vec<_> = self.strategy
+            .ask(rng)
+            .into_iter()
+            .map(I::create)
+            .collect();
+
+        self.asked = true;
+        genealogy.record_founders(new_population.len());
+
+        let stats = Statistics::new(population);
+        return (new_population, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        return TestIndividual::create(genes.iter().cloned().collect());
+    }
+
+    fn optimize(optimizer: &mut dyn Optimizer<TestIndividual>) -> Vec<TestIndividual> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut population = vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+        let mut genealogy = Genealogy::new(population.len());
+
+        for _ in 0..10 {
+            let (new_population, _) = optimizer.evolve(&mut rng, &population, &mut genealogy);
+            population = new_population;
+
+            assert_eq!(genealogy.current().len(), population.len());
+        }
+
+        return population;
+    }
+
+    mod genetic_algorithm {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let population = optimize(&mut ga);
+
+            // Same outcome as calling `GeneticAlgorithm::evolve()` directly (see `tests::test`)
+            assert_eq!(population[0], individual(&[0.4476949, 2.0648358, 4.3058133]));
+        }
+    }
+
+    mod ask_tell {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let parents = vec![vec![0.0; 3].into_iter().collect()];
+            let es = MuLambdaEs::new(parents, 6, 0.5, EsSelection::Plus);
+            let mut optimizer = Box::new(AskTellOptimizer::new(es));
+
+            let population = optimize(&mut optimizer);
+            assert_eq!(population.len(), 6);
+
+            // (μ+λ) never gets worse, and after 9 rounds of telling it should've found something
+            // better than the all-zeros starting point
+            let best = optimizer.strategy().parents().next().unwrap();
+            assert!(best.iter().sum::<f32>() > 0.0);
+        }
+    }
+}
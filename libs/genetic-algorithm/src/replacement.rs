@@ -0,0 +1,36 @@
+use rand::RngCore;
+
+pub use self::{
+    crowding::CrowdingReplacement,
+    oldest::ReplaceOldest,
+    random::ReplaceRandom,
+    tournament::TournamentReplacement,
+    worst::ReplaceWorst,
+};
+
+use crate::Individual;
+
+mod crowding;
+mod oldest;
+mod random;
+mod tournament;
+mod worst;
+
+/// Decides which members of a population make room for freshly bred children during a
+/// steady-state evolution (see `GeneticAlgorithm::evolve_steady_state`).
+///
+/// The population is assumed to be ordered from the oldest to the youngest individual.
+pub trait ReplacementMethod {
+    /// Returns one index into `population` for each of the `children`; the individual at that
+    /// index dies and the corresponding child takes its place.
+    ///
+    /// Returned indices must be distinct.
+    fn select_victims<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        children: &[I],
+    ) -> Vec<usize>
+        where
+            I: Individual;
+}
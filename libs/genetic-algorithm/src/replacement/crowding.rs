@@ -0,0 +1,88 @@
+use rand::RngCore;
+use rand::seq::SliceRandom;
+
+use crate::{Chromosome, Individual, ReplacementMethod};
+
+/// For each child, draws `crowd_size` random individuals and replaces the one whose
+/// chromosome is the most similar to the child's.
+///
+/// Since children only ever push out their look-alikes, different niches of the population
+/// don't get overrun by a single successful strategy.
+#[derive(Clone, Debug)]
+pub struct CrowdingReplacement {
+    crowd_size: usize,
+}
+
+impl CrowdingReplacement {
+    pub fn new(crowd_size: usize) -> Self {
+        assert!(crowd_size > 0);
+        return Self { crowd_size };
+    }
+}
+
+impl ReplacementMethod for CrowdingReplacement {
+    fn select_victims<I>(&self, rng: &mut dyn RngCore, population: &[I], children: &[I]) -> Vec<usize> where I: Individual {
+        assert!(children.len() <= population.len());
+
+        // Individuals that haven't been picked as a victim yet
+        let mut alive: Vec<_> = (0..population.len()).collect();
+
+        return children
+            .iter()
+            .map(|child| {
+                let victim = *alive
+                    .choose_multiple(rng, self.crowd_size)
+                    .min_by(|&&a, &&b| {
+                        let distance_a = distance(population[a].chromosome(), child.chromosome());
+                        let distance_b = distance(population[b].chromosome(), child.chromosome());
+                        return distance_a.total_cmp(&distance_b);
+                    })
+                    .unwrap();
+
+                alive.retain(|&idx| idx != victim);
+                return victim;
+            })
+            .collect();
+    }
+}
+
+/// Squared euclidean distance between two chromosomes
+fn distance(a: &Chromosome, b: &Chromosome) -> f32 {
+    assert_eq!(a.len(), b.len());
+
+    return a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| (a - b).powi(2))
+        .sum();
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::TestIndividual;
+
+    use super::*;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        return TestIndividual::create(genes.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[5.0, 5.0]),
+            individual(&[-5.0, 5.0]),
+        ];
+        let children = vec![individual(&[4.0, 6.0]), individual(&[-4.0, 4.0])];
+
+        let actual = CrowdingReplacement::new(3).select_victims(&mut rng, &population, &children);
+
+        assert_eq!(actual, vec![1, 2]);
+    }
+}
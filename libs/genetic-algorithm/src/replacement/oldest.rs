@@ -0,0 +1,48 @@
+use rand::RngCore;
+
+use crate::{Individual, ReplacementMethod};
+
+/// Children replace the individuals that have been alive for the longest time (first in,
+/// first out).
+#[derive(Clone, Debug, Default)]
+pub struct ReplaceOldest;
+
+impl ReplaceOldest {
+    pub fn new() -> Self {
+        return Self;
+    }
+}
+
+impl ReplacementMethod for ReplaceOldest {
+    fn select_victims<I>(&self, _: &mut dyn RngCore, population: &[I], children: &[I]) -> Vec<usize> where I: Individual {
+        assert!(children.len() <= population.len());
+
+        return (0..children.len()).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::TestIndividual;
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+        ];
+        let children = vec![TestIndividual::new(0.0), TestIndividual::new(0.0)];
+
+        let actual = ReplaceOldest::new().select_victims(&mut rng, &population, &children);
+
+        assert_eq!(actual, vec![0, 1]);
+    }
+}
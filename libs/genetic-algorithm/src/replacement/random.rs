@@ -0,0 +1,47 @@
+use rand::RngCore;
+use rand::seq::index;
+
+use crate::{Individual, ReplacementMethod};
+
+/// Children replace individuals picked uniformly at random, regardless of their fitness.
+#[derive(Clone, Debug, Default)]
+pub struct ReplaceRandom;
+
+impl ReplaceRandom {
+    pub fn new() -> Self {
+        return Self;
+    }
+}
+
+impl ReplacementMethod for ReplaceRandom {
+    fn select_victims<I>(&self, rng: &mut dyn RngCore, population: &[I], children: &[I]) -> Vec<usize> where I: Individual {
+        assert!(children.len() <= population.len());
+
+        return index::sample(rng, population.len(), children.len()).into_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::TestIndividual;
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population: Vec<_> = (0..10).map(|n| TestIndividual::new(n as f32)).collect();
+        let children = vec![TestIndividual::new(0.0); 5];
+
+        let mut actual = ReplaceRandom::new().select_victims(&mut rng, &population, &children);
+        assert_eq!(actual.len(), 5);
+
+        actual.sort();
+        actual.dedup();
+        assert_eq!(actual.len(), 5);
+    }
+}
@@ -0,0 +1,83 @@
+use rand::RngCore;
+use rand::seq::SliceRandom;
+
+use crate::{Individual, ReplacementMethod};
+
+/// For each child, draws `size` random individuals and replaces the least fit one among them.
+///
+/// Smaller tournaments are gentler to weak individuals:
+/// - size = 1 behaves just like `ReplaceRandom`
+/// - size = population size behaves just like `ReplaceWorst`
+#[derive(Clone, Debug)]
+pub struct TournamentReplacement {
+    size: usize,
+}
+
+impl TournamentReplacement {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+        return Self { size };
+    }
+}
+
+impl ReplacementMethod for TournamentReplacement {
+    fn select_victims<I>(&self, rng: &mut dyn RngCore, population: &[I], children: &[I]) -> Vec<usize> where I: Individual {
+        assert!(children.len() <= population.len());
+
+        // Individuals that haven't been picked as a victim yet
+        let mut alive: Vec<_> = (0..population.len()).collect();
+
+        return children
+            .iter()
+            .map(|_| {
+                let victim = *alive
+                    .choose_multiple(rng, self.size)
+                    .min_by(|&&a, &&b| population[a].fitness().total_cmp(&population[b].fitness()))
+                    .unwrap();
+
+                alive.retain(|&idx| idx != victim);
+                return victim;
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::TestIndividual;
+
+    use super::*;
+
+    #[test]
+    fn full_tournament_picks_the_worst() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ];
+        let children = vec![TestIndividual::new(0.0), TestIndividual::new(0.0)];
+
+        let actual = TournamentReplacement::new(4).select_victims(&mut rng, &population, &children);
+
+        assert_eq!(actual, vec![1, 0]);
+    }
+
+    #[test]
+    fn never_picks_the_same_victim_twice() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population: Vec<_> = (0..10).map(|n| TestIndividual::new(n as f32)).collect();
+        let children = vec![TestIndividual::new(0.0); 10];
+
+        let mut actual = TournamentReplacement::new(3).select_victims(&mut rng, &population, &children);
+        actual.sort();
+
+        assert_eq!(actual, (0..10).collect::<Vec<_>>());
+    }
+}
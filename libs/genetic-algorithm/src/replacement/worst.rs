@@ -0,0 +1,52 @@
+use rand::RngCore;
+
+use crate::{Individual, ReplacementMethod};
+
+/// Children replace the least fit individuals of the population.
+#[derive(Clone, Debug, Default)]
+pub struct ReplaceWorst;
+
+impl ReplaceWorst {
+    pub fn new() -> Self {
+        return Self;
+    }
+}
+
+impl ReplacementMethod for ReplaceWorst {
+    fn select_victims<I>(&self, _: &mut dyn RngCore, population: &[I], children: &[I]) -> Vec<usize> where I: Individual {
+        assert!(children.len() <= population.len());
+
+        let mut indices: Vec<_> = (0..population.len()).collect();
+        indices.sort_by(|&a, &b| population[a].fitness().total_cmp(&population[b].fitness()));
+        indices.truncate(children.len());
+
+        return indices;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::TestIndividual;
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(0.0),
+        ];
+        let children = vec![TestIndividual::new(0.0), TestIndividual::new(0.0)];
+
+        let actual = ReplaceWorst::new().select_victims(&mut rng, &population, &children);
+
+        assert_eq!(actual, vec![3, 1]);
+    }
+}
@@ -0,0 +1,26 @@
+pub use self::{
+    boltzmann::BoltzmannScaling,
+    linear::LinearScaling,
+    offset::OffsetScaling,
+    rank::RankScaling,
+    sigma_truncation::SigmaTruncation,
+};
+
+mod boltzmann;
+mod linear;
+mod offset;
+mod rank;
+mod sigma_truncation;
+
+/// Transforms the raw fitness values of a population before they are handed to a
+/// `SelectionMethod` (see `ScaledSelection`).
+///
+/// Implementations receive the fitness of every individual (in population order) and must
+/// return the same number of values, in the same order.
+pub trait FitnessScaling {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32>;
+}
+
+pub(crate) fn mean(fitnesses: &[f32]) -> f32 {
+    return fitnesses.iter().sum::<f32>() / (fitnesses.len() as f32);
+}
@@ -0,0 +1,49 @@
+use crate::FitnessScaling;
+
+/// Boltzmann scaling:
+///
+/// f' = exp((f - max) / temperature)
+///
+/// High temperatures flatten the differences between individuals, low temperatures exaggerate
+/// them. Subtracting `max` doesn't change the proportions, but keeps `exp()` from overflowing.
+#[derive(Clone, Debug)]
+pub struct BoltzmannScaling {
+    temperature: f32,
+}
+
+impl BoltzmannScaling {
+    pub fn new(temperature: f32) -> Self {
+        assert!(temperature > 0.0);
+        return Self { temperature };
+    }
+}
+
+impl FitnessScaling for BoltzmannScaling {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32> {
+        let max = fitnesses.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        return fitnesses
+            .iter()
+            .map(|fitness| ((fitness - max) / self.temperature).exp())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let actual = BoltzmannScaling::new(1.0).scale(&[-1.0, 0.0, 1.0]);
+        let expected = vec![(-2.0f32).exp(), (-1.0f32).exp(), 1.0];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn does_not_overflow() {
+        let actual = BoltzmannScaling::new(0.01).scale(&[1000.0, 999.0]);
+        assert!(actual.iter().all(|fitness| fitness.is_finite()));
+    }
+}
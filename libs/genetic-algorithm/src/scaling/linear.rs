@@ -0,0 +1,101 @@
+use crate::FitnessScaling;
+use crate::scaling::mean;
+
+/// Goldberg's linear scaling:
+///
+/// f' = a * f + b
+///
+/// where `a` and `b` are chosen so that the average fitness stays the same, while the best
+/// individual gets `multiplier` times the average. If that would push the worst individual
+/// below zero, the line is instead pinned so that the worst individual lands exactly on zero.
+///
+/// Negative fitnesses are shifted up first, so that the worst individual starts at zero - the
+/// formula only makes sense for a non-negative average.
+#[derive(Clone, Debug)]
+pub struct LinearScaling {
+    /// Expected number of copies of the best individual:
+    /// - 1.0 = no selection pressure at all
+    /// - 2.0 = the usual choice
+    multiplier: f32,
+}
+
+impl LinearScaling {
+    pub fn new(multiplier: f32) -> Self {
+        assert!(multiplier >= 1.0);
+        return Self { multiplier };
+    }
+}
+
+impl Default for LinearScaling {
+    fn default() -> Self {
+        return Self::new(2.0);
+    }
+}
+
+impl FitnessScaling for LinearScaling {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32> {
+        let min = fitnesses.iter().cloned().fold(f32::INFINITY, f32::min);
+
+        if min < 0.0 {
+            let shifted: Vec<_> = fitnesses.iter().map(|fitness| fitness - min).collect();
+            return self.scale(&shifted);
+        }
+
+        let avg = mean(fitnesses);
+        let max = fitnesses.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        if max - avg <= f32::EPSILON {
+            // Everyone's equal, so there's nothing to scale
+            return vec![1.0; fitnesses.len()];
+        }
+
+        let (a, b) = if min > (self.multiplier * avg - max) / (self.multiplier - 1.0) {
+            let a = (self.multiplier - 1.0) * avg / (max - avg);
+            (a, avg * (1.0 - a))
+        } else {
+            let a = avg / (avg - min);
+            (a, -min * a)
+        };
+
+        return fitnesses
+            .iter()
+            .map(|fitness| (a * fitness + b).max(0.0))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preserves_the_average_and_stretches_the_best() {
+        let actual = LinearScaling::new(2.0).scale(&[2.0, 4.0, 6.0]);
+        let expected = vec![0.0, 4.0, 8.0];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn never_goes_negative() {
+        let actual = LinearScaling::new(2.0).scale(&[1.0, 9.0, 10.0, 10.0]);
+        assert!(actual.iter().all(|&fitness| fitness >= 0.0));
+        approx::assert_relative_eq!(actual[0], 0.0);
+    }
+
+    #[test]
+    fn negative_fitnesses() {
+        let actual = LinearScaling::new(2.0).scale(&[-3.0, -2.0, -1.0]);
+        let expected = vec![0.0, 1.0, 2.0];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn flat_population() {
+        let actual = LinearScaling::new(2.0).scale(&[0.0, 0.0, 0.0]);
+        let expected = vec![1.0, 1.0, 1.0];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+}
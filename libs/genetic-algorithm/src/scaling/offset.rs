@@ -0,0 +1,49 @@
+use crate::FitnessScaling;
+
+/// Shifts every fitness so that the worst individual ends up at `epsilon`:
+///
+/// f' = f - min + epsilon
+///
+/// Useful when the fitness function can go negative (or be all zeroes), which
+/// `RouletteWheelSelection` cannot make sense of.
+#[derive(Clone, Debug)]
+pub struct OffsetScaling {
+    epsilon: f32,
+}
+
+impl OffsetScaling {
+    pub fn new(epsilon: f32) -> Self {
+        assert!(epsilon >= 0.0);
+        return Self { epsilon };
+    }
+}
+
+impl Default for OffsetScaling {
+    fn default() -> Self {
+        return Self::new(f32::EPSILON);
+    }
+}
+
+impl FitnessScaling for OffsetScaling {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32> {
+        let min = fitnesses.iter().cloned().fold(f32::INFINITY, f32::min);
+
+        return fitnesses
+            .iter()
+            .map(|fitness| fitness - min + self.epsilon)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let actual = OffsetScaling::new(0.5).scale(&[-2.0, 0.0, 3.0]);
+        let expected = vec![0.5, 2.5, 5.5];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+}
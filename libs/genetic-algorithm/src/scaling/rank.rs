@@ -0,0 +1,62 @@
+use crate::FitnessScaling;
+
+/// Rank normalization: throws the actual fitness values away and keeps only their order.
+///
+/// The worst individual gets `1 / n`, the best one gets `1.0`; ties share the same (average)
+/// rank.
+#[derive(Clone, Debug, Default)]
+pub struct RankScaling;
+
+impl RankScaling {
+    pub fn new() -> Self {
+        return Self;
+    }
+}
+
+impl FitnessScaling for RankScaling {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32> {
+        let mut order: Vec<_> = (0..fitnesses.len()).collect();
+        order.sort_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]));
+
+        let mut ranks = vec![0.0; fitnesses.len()];
+        let mut i = 0;
+
+        while i < order.len() {
+            // Find the run of tied individuals starting at `i`
+            let mut j = i;
+            while j + 1 < order.len() && fitnesses[order[j + 1]] == fitnesses[order[i]] {
+                j += 1;
+            }
+
+            let rank = ((i + j) as f32 / 2.0 + 1.0) / (fitnesses.len() as f32);
+            for &idx in &order[i..=j] {
+                ranks[idx] = rank;
+            }
+
+            i = j + 1;
+        }
+
+        return ranks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let actual = RankScaling::new().scale(&[10.0, -5.0, 100.0, 0.0]);
+        let expected = vec![0.75, 0.25, 1.0, 0.5];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn ties() {
+        let actual = RankScaling::new().scale(&[1.0, 1.0, 2.0, 0.0]);
+        let expected = vec![0.625, 0.625, 1.0, 0.25];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::FitnessScaling;
+use crate::scaling::mean;
+
+/// Sigma truncation:
+///
+/// f' = max(0, f - (avg - c * σ))
+///
+/// Individuals that are more than `c` standard deviations below the average are given a
+/// fitness of zero.
+#[derive(Clone, Debug)]
+pub struct SigmaTruncation {
+    c: f32,
+}
+
+impl SigmaTruncation {
+    pub fn new(c: f32) -> Self {
+        assert!(c >= 0.0);
+        return Self { c };
+    }
+}
+
+impl Default for SigmaTruncation {
+    fn default() -> Self {
+        return Self::new(2.0);
+    }
+}
+
+impl FitnessScaling for SigmaTruncation {
+    fn scale(&self, fitnesses: &[f32]) -> Vec<f32> {
+        let avg = mean(fitnesses);
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - avg).powi(2))
+            .sum::<f32>() / (fitnesses.len() as f32);
+        let threshold = avg - self.c * variance.sqrt();
+
+        return fitnesses
+            .iter()
+            .map(|fitness| (fitness - threshold).max(0.0))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // avg = 5.0, σ = 3.0
+        let actual = SigmaTruncation::new(1.0).scale(&[2.0, 2.0, 8.0, 8.0]);
+        let expected = vec![0.0, 0.0, 6.0, 6.0];
+
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+}
@@ -1,10 +1,12 @@
 use rand::RngCore;
 
 pub use roulette_wheel::RouletteWheelSelection;
+pub use scaled::ScaledSelection;
 
 use crate::individual::Individual;
 
 mod roulette_wheel;
+mod scaled;
 
 pub trait SelectionMethod {
     fn select<'a, I>(
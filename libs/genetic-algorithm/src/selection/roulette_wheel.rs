@@ -1,4 +1,4 @@
-use rand::{RngCore};
+use rand::RngCore;
 use rand::seq::SliceRandom;
 
 use crate::{Individual, SelectionMethod};
@@ -14,17 +14,27 @@ impl RouletteWheelSelection {
 
 impl SelectionMethod for RouletteWheelSelection {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I where I: Individual {
+        assert!(!population.is_empty(), "got an empty population");
+
+        // Negative fitness has no sensible meaning on a roulette wheel, so it's treated as zero;
+        // use e.g. `OffsetScaling` if negative values should still be told apart.
+        //
+        // If nobody has any positive fitness (e.g. no bird has eaten anything during the whole
+        // generation), there's no wheel to spin - fall back to picking uniformly instead.
         return population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("got an empty population");
+            .choose_weighted(rng, |individual| fitness_weight(individual.fitness()))
+            .unwrap_or_else(|_| population.choose(rng).unwrap());
     }
 }
 
+fn fitness_weight(fitness: f32) -> f32 {
+    return if fitness.is_finite() { fitness.max(0.0) } else { 0.0 };
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
-    use maplit;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
@@ -61,4 +71,39 @@ mod tests {
 
         assert_eq!(actual_histogram, expected_histogram);
     }
+
+    #[test]
+    fn zero_fitness_falls_back_to_uniform() {
+        let method = RouletteWheelSelection::new();
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(0.0),
+            TestIndividual::new(0.0),
+            TestIndividual::new(0.0),
+        ];
+
+        let selected: Vec<_> = (0..100)
+            .map(|_| method.select(&mut rng, &population) as *const _)
+            .collect();
+
+        for individual in &population {
+            assert!(selected.contains(&(individual as *const _)));
+        }
+    }
+
+    #[test]
+    fn negative_fitness_is_never_selected() {
+        let method = RouletteWheelSelection::new();
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(-5.0),
+            TestIndividual::new(1.0),
+        ];
+
+        for _ in 0..100 {
+            assert_eq!(method.select(&mut rng, &population).fitness(), 1.0);
+        }
+    }
 }
\ No newline at end of file
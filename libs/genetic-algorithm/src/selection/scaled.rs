@@ -0,0 +1,129 @@
+use rand::RngCore;
+
+use crate::{Chromosome, FitnessScaling, Individual, SelectionMethod};
+
+/// Runs the population's fitness through a `FitnessScaling` before letting the wrapped
+/// `SelectionMethod` pick an individual, e.g.:
+///
+/// ScaledSelection::new(RouletteWheelSelection::new(), RankScaling::new())
+#[derive(Clone, Debug, Default)]
+pub struct ScaledSelection<S, F> {
+    selection_method: S,
+    fitness_scaling: F,
+}
+
+impl<S, F> ScaledSelection<S, F>
+    where
+        S: SelectionMethod,
+        F: FitnessScaling,
+{
+    pub fn new(selection_method: S, fitness_scaling: F) -> Self {
+        return Self { selection_method, fitness_scaling };
+    }
+}
+
+impl<S, F> SelectionMethod for ScaledSelection<S, F>
+    where
+        S: SelectionMethod,
+        F: FitnessScaling,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I where I: Individual {
+        let fitnesses: Vec<_> = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .collect();
+
+        let fitnesses = self.fitness_scaling.scale(&fitnesses);
+        assert_eq!(fitnesses.len(), population.len());
+
+        let scaled_population: Vec<_> = population
+            .iter()
+            .zip(fitnesses)
+            .map(|(individual, fitness)| ScaledIndividual { individual, fitness })
+            .collect();
+
+        return self.selection_method.select(rng, &scaled_population).individual;
+    }
+}
+
+/// Borrows an individual, presenting the scaled fitness in place of the original one.
+struct ScaledIndividual<'a, I> {
+    individual: &'a I,
+    fitness: f32,
+}
+
+impl<'a, I> Individual for ScaledIndividual<'a, I>
+    where
+        I: Individual
+{
+    fn fitness(&self) -> f32 {
+        return self.fitness;
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        return self.individual.chromosome();
+    }
+
+    fn create(_: Chromosome) -> Self {
+        unreachable!("ScaledIndividual is only ever used for selection");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{OffsetScaling, RankScaling, RouletteWheelSelection, TestIndividual};
+
+    use super::*;
+
+    fn histogram(method: &impl SelectionMethod, population: &[TestIndividual]) -> BTreeMap<i32, usize> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        return (0..1000)
+            .map(|_| method.select(&mut rng, population))
+            .fold(Default::default(), |mut histogram, individual| {
+                *histogram.entry(individual.fitness() as _)
+                    .or_default() += 1;
+                return histogram;
+            });
+    }
+
+    #[test]
+    fn selects_original_individuals() {
+        let method = ScaledSelection::new(RouletteWheelSelection::new(), RankScaling::new());
+
+        let population = vec![
+            TestIndividual::new(-10.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(100.0),
+        ];
+
+        let actual_histogram = histogram(&method, &population);
+
+        // Ranks are 1/3, 2/3 and 3/3, so the best individual should be picked about half of the
+        // time, even though its raw fitness is a hundred times bigger.
+        assert_eq!(actual_histogram.keys().cloned().collect::<Vec<_>>(), vec![-10, 1, 100]);
+        assert!((450..550).contains(&actual_histogram[&100]));
+    }
+
+    #[test]
+    fn handles_negative_fitness() {
+        let method = ScaledSelection::new(RouletteWheelSelection::new(), OffsetScaling::new(0.0));
+
+        let population = vec![
+            TestIndividual::new(-3.0),
+            TestIndividual::new(-2.0),
+            TestIndividual::new(-1.0),
+        ];
+
+        let actual_histogram = histogram(&method, &population);
+
+        // After offsetting, the worst individual has zero fitness and is never picked
+        assert!(!actual_histogram.contains_key(&-3));
+        assert!(actual_histogram[&-1] > actual_histogram[&-2]);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::*;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     pub(crate) neurons: Vec<Neuron>,
 }
@@ -10,6 +10,7 @@ mod layer_topology;
 mod neuron;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Network {
     layers: Vec<Layer>,
 }
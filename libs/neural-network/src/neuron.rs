@@ -1,6 +1,7 @@
 use crate::*;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Neuron {
     pub(crate) bias: f32,
     pub(crate) weights: Vec<f32>,
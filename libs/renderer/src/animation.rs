@@ -0,0 +1,114 @@
+use std::io::{self, Write};
+
+use crate::*;
+
+/// How hard GIF's palette quantization tries, from 1 (best, slowest) to 30 (worst, fastest)
+const QUANTIZATION_SPEED: i32 = 10;
+
+/// Animated GIF, built frame by frame
+pub struct Animation<W>
+    where
+        W: Write,
+{
+    encoder: gif::Encoder<W>,
+    width: u16,
+    height: u16,
+
+    /// How long each frame is shown, in hundredths of a second
+    delay: u16,
+}
+
+impl<W> Animation<W>
+    where
+        W: Write,
+{
+    /// Creates an animation that plays at `fps` frames per second, looping forever
+    pub fn new(writer: W, width: u16, height: u16, fps: u16) -> Result<Self, gif::EncodingError> {
+        let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        return Ok(Self {
+            encoder,
+            width,
+            height,
+            delay: (100 / fps.max(1)).max(1),
+        });
+    }
+
+    /// Appends `canvas` as the next frame; it must be of the same size as the animation
+    pub fn add_frame(&mut self, canvas: &Canvas) -> Result<(), gif::EncodingError> {
+        assert_eq!(
+            [canvas.width(), canvas.height()],
+            [self.width as u32, self.height as u32],
+            "frame doesn't match the animation's size",
+        );
+
+        let mut frame = gif::Frame::from_rgb_speed(
+            self.width,
+            self.height,
+            &canvas.as_rgb(),
+            QUANTIZATION_SPEED,
+        );
+
+        frame.delay = self.delay;
+
+        return self.encoder.write_frame(&frame);
+    }
+
+    /// Finishes the animation, returning the underlying writer
+    pub fn finish(self) -> io::Result<W> {
+        return self.encoder.into_inner();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color::rgb(0, 0, 0);
+    const WHITE: Color = Color::rgb(255, 255, 255);
+
+    mod add_frame {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut animation = Animation::new(Vec::new(), 4, 4, 25).unwrap();
+
+            for x in 0..3 {
+                let mut canvas = Canvas::new(4, 4, BLACK);
+                canvas.fill_rect([x as f32, 0.0], [x as f32 + 1.0, 4.0], WHITE);
+
+                animation.add_frame(&canvas).unwrap();
+            }
+
+            let gif = animation.finish().unwrap();
+
+            let mut options = gif::DecodeOptions::new();
+            options.set_color_output(gif::ColorOutput::RGBA);
+
+            let mut decoder = options.read_info(gif.as_slice()).unwrap();
+            let mut frames = 0;
+
+            while let Some(frame) = decoder.read_next_frame().unwrap() {
+                assert_eq!([frame.width, frame.height], [4, 4]);
+                assert_eq!(frame.delay, 4);
+
+                // (the frame's white column is where it's been drawn)
+                assert_eq!(&frame.buffer[frames * 4..frames * 4 + 4], [255, 255, 255, 255]);
+                assert_eq!(&frame.buffer[12..16], [0, 0, 0, 255]);
+
+                frames += 1;
+            }
+
+            assert_eq!(frames, 3);
+        }
+
+        #[test]
+        #[should_panic(expected = "frame doesn't match the animation's size")]
+        fn mismatched_size() {
+            let mut animation = Animation::new(Vec::new(), 4, 4, 25).unwrap();
+            animation.add_frame(&Canvas::new(2, 2, BLACK)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::f32::consts::PI;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        return Self { r, g, b };
+    }
+
+    /// Same as CSS's `hsl()`, except that all the components are in <0, 1>
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let hue = hue.rem_euclid(1.0) * 6.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = lightness - chroma / 2.0;
+        let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+
+        return Self::rgb(channel(r), channel(g), channel(b));
+    }
+
+    /// Returns `self` painted over with `other`, which is `alpha` opaque
+    pub fn mix(self, other: Self, alpha: f32) -> Self {
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * alpha).round() as u8;
+
+        return Self::rgb(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+        );
+    }
+}
+
+/// RGB image that shapes can be drawn on.
+///
+/// All the coordinates are in pixels, with <0, 0> being the top-left corner of the image; a pixel
+/// gets painted when its center lies inside the shape (there's no anti-aliasing).
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        return Self {
+            width,
+            height,
+            pixels: vec![background; (width * height) as usize],
+        };
+    }
+
+    pub fn width(&self) -> u32 {
+        return self.width;
+    }
+
+    pub fn height(&self) -> u32 {
+        return self.height;
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        return self.pixels[(y * self.width + x) as usize];
+    }
+
+    /// Returns the pixels as `[r, g, b, r, g, b, ...]`, row by row
+    pub fn as_rgb(&self) -> Vec<u8> {
+        return self
+            .pixels
+            .iter()
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect();
+    }
+
+    pub fn fill_circle(&mut self, center: [f32; 2], radius: f32, color: Color) {
+        self.fill(
+            [center[0] - radius, center[1] - radius],
+            [center[0] + radius, center[1] + radius],
+            color,
+            1.0,
+            |point| distance_sq(point, center) <= radius * radius,
+        );
+    }
+
+    pub fn fill_rect(&mut self, min: [f32; 2], max: [f32; 2], color: Color) {
+        self.fill(min, max, color, 1.0, |_| true);
+    }
+
+    pub fn fill_triangle(&mut self, [a, b, c]: [[f32; 2]; 3], color: Color) {
+        let min = [a[0].min(b[0]).min(c[0]), a[1].min(b[1]).min(c[1])];
+        let max = [a[0].max(b[0]).max(c[0]), a[1].max(b[1]).max(c[1])];
+
+        self.fill(min, max, color, 1.0, |point| {
+            let ab = cross(a, b, point);
+            let bc = cross(b, c, point);
+            let ca = cross(c, a, point);
+
+            // (the point lies on the same side of all the edges, whichever way the triangle is
+            // wound)
+            return (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0);
+        });
+    }
+
+    /// Fills a "slice of pizza" spanning `angle` radians, centered around `direction` (an angle
+    /// relative to the X axis, just like `Rotation2::angle()`); only `alpha` of the color is
+    /// painted, so that whatever's below remains visible
+    pub fn fill_sector(
+        &mut self,
+        center: [f32; 2],
+        radius: f32,
+        direction: f32,
+        angle: f32,
+        color: Color,
+        alpha: f32,
+    ) {
+        self.fill(
+            [center[0] - radius, center[1] - radius],
+            [center[0] + radius, center[1] + radius],
+            color,
+            alpha,
+            |point| {
+                if distance_sq(point, center) > radius * radius {
+                    return false;
+                }
+
+                let offset = (point[1] - center[1]).atan2(point[0] - center[0]) - direction;
+                let offset = (offset + PI).rem_euclid(2.0 * PI) - PI;
+
+                return offset.abs() <= angle / 2.0;
+            },
+        );
+    }
+
+    pub fn draw_line(&mut self, from: [f32; 2], to: [f32; 2], width: f32, color: Color) {
+        let half = width / 2.0;
+
+        self.fill(
+            [from[0].min(to[0]) - half, from[1].min(to[1]) - half],
+            [from[0].max(to[0]) + half, from[1].max(to[1]) + half],
+            color,
+            1.0,
+            |point| {
+                let line = [to[0] - from[0], to[1] - from[1]];
+                let len_sq = line[0] * line[0] + line[1] * line[1];
+
+                let t = if len_sq > 0.0 {
+                    (((point[0] - from[0]) * line[0] + (point[1] - from[1]) * line[1]) / len_sq)
+                        .clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+
+                let closest = [from[0] + line[0] * t, from[1] + line[1] * t];
+
+                return distance_sq(point, closest) <= half * half;
+            },
+        );
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.as_rgb())?;
+
+        return writer.finish();
+    }
+
+    /// Paints each pixel within the `min`-`max` bounding box whose center satisfies `contains`
+    fn fill(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        color: Color,
+        alpha: f32,
+        contains: impl Fn([f32; 2]) -> bool,
+    ) {
+        let [x0, y0] = min.map(|coord| coord.floor().max(0.0) as u32);
+        let x1 = (max[0].ceil().max(0.0) as u32).min(self.width);
+        let y1 = (max[1].ceil().max(0.0) as u32).min(self.height);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if !contains([x as f32 + 0.5, y as f32 + 0.5]) {
+                    continue;
+                }
+
+                let pixel = &mut self.pixels[(y * self.width + x) as usize];
+                *pixel = pixel.mix(color, alpha);
+            }
+        }
+    }
+}
+
+fn distance_sq(a: [f32; 2], b: [f32; 2]) -> f32 {
+    return (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2);
+}
+
+/// Which side of the `a`-`b` edge `point` lies on
+fn cross(a: [f32; 2], b: [f32; 2], point: [f32; 2]) -> f32 {
+    return (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color::rgb(0, 0, 0);
+    const WHITE: Color = Color::rgb(255, 255, 255);
+
+    /// Draws the canvas as ASCII art, `#` being anything that's not black
+    fn ascii(canvas: &Canvas) -> Vec<String> {
+        return (0..canvas.height())
+            .map(|y| {
+                return (0..canvas.width())
+                    .map(|x| if canvas.pixel(x, y) == BLACK { '.' } else { '#' })
+                    .collect();
+            })
+            .collect();
+    }
+
+    mod hsl {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(Color::hsl(0.0, 1.0, 0.5), Color::rgb(255, 0, 0));
+            assert_eq!(Color::hsl(1.0 / 3.0, 1.0, 0.5), Color::rgb(0, 255, 0));
+            assert_eq!(Color::hsl(2.0 / 3.0, 1.0, 0.5), Color::rgb(0, 0, 255));
+            assert_eq!(Color::hsl(0.5, 1.0, 0.75), Color::rgb(128, 255, 255));
+            assert_eq!(Color::hsl(0.25, 0.0, 1.0), WHITE);
+        }
+    }
+
+    mod mix {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(BLACK.mix(WHITE, 0.0), BLACK);
+            assert_eq!(BLACK.mix(WHITE, 0.5), Color::rgb(128, 128, 128));
+            assert_eq!(BLACK.mix(WHITE, 1.0), WHITE);
+        }
+    }
+
+    mod fill_circle {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut canvas = Canvas::new(7, 7, BLACK);
+            canvas.fill_circle([3.5, 3.5], 2.0, WHITE);
+
+            assert_eq!(
+                ascii(&canvas),
+                [
+                    ".......",
+                    "...#...",
+                    "..###..",
+                    ".#####.",
+                    "..###..",
+                    "...#...",
+                    ".......",
+                ]
+            );
+        }
+
+        #[test]
+        fn partially_outside() {
+            let mut canvas = Canvas::new(3, 3, BLACK);
+            canvas.fill_circle([0.0, 0.0], 2.0, WHITE);
+
+            assert_eq!(ascii(&canvas), ["##.", "#..", "..."]);
+        }
+    }
+
+    mod fill_triangle {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut canvas = Canvas::new(5, 5, BLACK);
+            canvas.fill_triangle([[0.0, 0.0], [5.0, 0.0], [0.0, 5.0]], WHITE);
+
+            assert_eq!(ascii(&canvas), ["#####", "####.", "###..", "##...", "#...."]);
+
+            // (the winding doesn't matter)
+            let mut other = Canvas::new(5, 5, BLACK);
+            other.fill_triangle([[0.0, 0.0], [0.0, 5.0], [5.0, 0.0]], WHITE);
+
+            assert_eq!(ascii(&other), ascii(&canvas));
+        }
+    }
+
+    mod fill_sector {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut canvas = Canvas::new(7, 7, BLACK);
+            canvas.fill_sector([3.5, 3.5], 3.0, 0.0, PI / 2.0, WHITE, 1.0);
+
+            assert_eq!(
+                ascii(&canvas),
+                [
+                    ".......",
+                    ".......",
+                    ".....#.",
+                    "...####",
+                    ".....#.",
+                    ".......",
+                    ".......",
+                ]
+            );
+        }
+
+        #[test]
+        fn translucent() {
+            let mut canvas = Canvas::new(3, 3, BLACK);
+            canvas.fill_sector([1.5, 1.5], 3.0, 0.0, 2.0 * PI, WHITE, 0.25);
+
+            assert_eq!(canvas.pixel(1, 1), Color::rgb(64, 64, 64));
+        }
+    }
+
+    mod draw_line {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut canvas = Canvas::new(5, 5, BLACK);
+            canvas.draw_line([0.0, 0.0], [5.0, 5.0], 1.0, WHITE);
+
+            assert_eq!(ascii(&canvas), ["#....", ".#...", "..#..", "...#.", "....#"]);
+        }
+    }
+
+    mod write_png {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut canvas = Canvas::new(4, 2, BLACK);
+            canvas.fill_rect([1.0, 0.0], [2.0, 1.0], Color::rgb(1, 2, 3));
+
+            let mut png = Vec::new();
+            canvas.write_png(&mut png).unwrap();
+
+            let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+            let mut pixels = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut pixels).unwrap();
+
+            assert_eq!([info.width, info.height], [4, 2]);
+            assert_eq!(pixels, canvas.as_rgb());
+            assert_eq!(&pixels[3..6], [1, 2, 3]);
+        }
+    }
+}
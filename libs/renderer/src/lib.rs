@@ -0,0 +1,13 @@
+//! Draws worlds of `lib-simulation` without a browser, into PNG images and animated GIFs
+
+use lib_simulation as sim;
+
+pub use self::{
+    animation::Animation,
+    canvas::{Canvas, Color},
+    renderer::Renderer,
+};
+
+mod animation;
+mod canvas;
+mod renderer;
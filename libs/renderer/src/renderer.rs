@@ -0,0 +1,308 @@
+use std::f32::consts::PI;
+
+use crate::*;
+
+/// Background of the page in the browser (see `www/src/ltf.css`)
+const BACKGROUND: Color = Color::rgb(31, 38, 57);
+const FOOD: Color = Color::rgb(0, 255, 128);
+const OBSTACLE: Color = Color::rgb(96, 96, 96);
+const PREY: Color = Color::rgb(255, 255, 255);
+const PREDATOR: Color = Color::rgb(255, 64, 64);
+
+/// How opaque vision cones are, so that they don't hide what the animals see
+const VISION_ALPHA: f32 = 0.08;
+
+/// Colors of heatmaps, from the coldest to the hottest
+const HEAT: [Color; 4] = [
+    BACKGROUND,
+    Color::rgb(192, 32, 64),
+    Color::rgb(255, 192, 0),
+    Color::rgb(255, 255, 255),
+];
+
+/// Draws worlds the same way the browser does (see `www/src/index.js`)
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    pub width: u32,
+    pub height: u32,
+
+    /// Whether to draw animals' fields of view
+    pub vision: bool,
+}
+
+impl Renderer {
+    pub fn render(&self, world: &sim::World) -> Canvas {
+        let mut canvas = Canvas::new(self.width, self.height, BACKGROUND);
+        let animals: Vec<_> = world
+            .animals()
+            .iter()
+            .chain(world.predators())
+            .filter(|animal| animal.is_alive())
+            .collect();
+
+        if self.vision {
+            for animal in &animals {
+                canvas.fill_sector(
+                    self.point(animal.position().into()),
+                    animal.eye().fov_range() * self.width as f32,
+                    animal.rotation().angle(),
+                    animal.eye().fov_angle(),
+                    color_of(animal),
+                    VISION_ALPHA,
+                );
+            }
+        }
+
+        for obstacle in world.obstacles() {
+            self.draw_obstacle(&mut canvas, obstacle);
+        }
+
+        for food in world.foods().iter().filter(|food| food.is_available()) {
+            canvas.fill_circle(
+                self.point(food.position().into()),
+                (0.01 / 2.0) * food.nutrition().sqrt() * self.width as f32,
+                FOOD,
+            );
+        }
+
+        for animal in &animals {
+            self.draw_animal(&mut canvas, animal);
+        }
+
+        return canvas;
+    }
+
+    /// Draws the heatmap over the entire image, its hottest cell being white
+    pub fn render_heatmap(&self, heatmap: &sim::Heatmap) -> Canvas {
+        let mut canvas = Canvas::new(self.width, self.height, BACKGROUND);
+        let max = heatmap.max();
+
+        if max <= 0.0 {
+            return canvas;
+        }
+
+        let cell = 1.0 / heatmap.size() as f32;
+
+        for y in 0..heatmap.size() {
+            for x in 0..heatmap.size() {
+                let min = [x as f32 * cell, y as f32 * cell];
+                let color = heat(heatmap.get(x, y) / max);
+
+                canvas.fill_rect(self.point(min), self.point(min.map(|coord| coord + cell)), color);
+            }
+        }
+
+        return canvas;
+    }
+
+    fn draw_obstacle(&self, canvas: &mut Canvas, obstacle: &sim::Obstacle) {
+        match *obstacle {
+            sim::Obstacle::Circle { center, radius } => {
+                canvas.fill_circle(self.point(center.into()), radius * self.width as f32, OBSTACLE);
+            }
+
+            sim::Obstacle::Rect { min, max } => {
+                canvas.fill_rect(self.point(min.into()), self.point(max.into()), OBSTACLE);
+            }
+
+            sim::Obstacle::Wall { from, to } => {
+                canvas.draw_line(self.point(from.into()), self.point(to.into()), 2.0, OBSTACLE);
+            }
+        }
+    }
+
+    /// Draws the animal as a triangle pointing where it's flying
+    fn draw_animal(&self, canvas: &mut Canvas, animal: &sim::Animal) {
+        // Predators are drawn a bit larger, so that they stand out
+        let size = match animal.species() {
+            sim::Species::Prey => 1.0,
+            sim::Species::Predator => 1.5,
+        } * animal.body().radius() * self.width as f32;
+
+        let [x, y] = self.point(animal.position().into());
+        let rotation = animal.rotation().angle();
+
+        let vertex = |angle: f32, size: f32| {
+            return [x + (rotation + angle).cos() * size, y + (rotation + angle).sin() * size];
+        };
+
+        canvas.fill_triangle(
+            [
+                vertex(0.0, size * 1.5),
+                vertex(2.0 / 3.0 * PI, size),
+                vertex(-2.0 / 3.0 * PI, size),
+            ],
+            color_of(animal),
+        );
+    }
+
+    /// Converts a point of the world, in <0, 1>, into pixels
+    fn point(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        return [x * self.width as f32, y * self.height as f32];
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        return Self {
+            width: 400,
+            height: 400,
+            vision: false,
+        };
+    }
+}
+
+/// Color of heat in <0, 1>
+fn heat(heat: f32) -> Color {
+    let heat = heat.clamp(0.0, 1.0) * (HEAT.len() - 1) as f32;
+    let idx = (heat as usize).min(HEAT.len() - 2);
+
+    return HEAT[idx].mix(HEAT[idx + 1], heat - idx as f32);
+}
+
+fn color_of(animal: &sim::Animal) -> Color {
+    let predator = animal.species() == sim::Species::Predator;
+
+    // Evolved bodies come with their own colors
+    if let Some(hue) = animal.body().color() {
+        return Color::hsl(hue, 1.0, if predator { 0.5 } else { 0.75 });
+    }
+
+    return if predator { PREDATOR } else { PREY };
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn world(config: &sim::SimulationConfig) -> sim::World {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        return sim::World::random(&mut rng, config);
+    }
+
+    fn pixel_at(canvas: &Canvas, position: [f32; 2]) -> Color {
+        return canvas.pixel(
+            (position[0] * canvas.width() as f32) as u32,
+            (position[1] * canvas.height() as f32) as u32,
+        );
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = sim::SimulationConfig::default();
+            let world = world(&config);
+            let canvas = Renderer::default().render(&world);
+
+            assert_eq!([canvas.width(), canvas.height()], [400, 400]);
+
+            for animal in world.animals() {
+                assert_eq!(pixel_at(&canvas, animal.position().into()), PREY);
+            }
+
+            for food in world.foods() {
+                let pixel = pixel_at(&canvas, food.position().into());
+                assert!(pixel == FOOD || pixel == PREY);
+            }
+        }
+
+        #[test]
+        fn predators() {
+            let config = sim::SimulationConfig {
+                predators: 2,
+                ..Default::default()
+            };
+
+            let world = world(&config);
+            let canvas = Renderer::default().render(&world);
+
+            for predator in world.predators() {
+                assert_eq!(pixel_at(&canvas, predator.position().into()), PREDATOR);
+            }
+        }
+
+        #[test]
+        fn vision() {
+            let config = sim::SimulationConfig {
+                animals: 1,
+                foods: 0,
+                ..Default::default()
+            };
+
+            let world = world(&config);
+            let animal = &world.animals()[0];
+
+            // A bit ahead of the bird, where it can see but where it isn't drawn
+            let distance = animal.eye().fov_range() / 2.0;
+            let rotation = animal.rotation().angle();
+
+            let ahead = [
+                animal.position().x + rotation.cos() * distance,
+                animal.position().y + rotation.sin() * distance,
+            ];
+
+            let without = Renderer::default().render(&world);
+            let with = Renderer { vision: true, ..Default::default() }.render(&world);
+
+            assert_eq!(pixel_at(&without, ahead), BACKGROUND);
+            assert_eq!(pixel_at(&with, ahead), BACKGROUND.mix(PREY, VISION_ALPHA));
+        }
+    }
+
+    mod render_heatmap {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = sim::SimulationConfig {
+                generation_length: 50,
+                heatmap_grid: 4,
+                ..Default::default()
+            };
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let stats = sim::Simulation::new(config, &mut rng).unwrap().train(&mut rng, None);
+            let occupancy = stats.occupancy().unwrap();
+
+            let canvas = Renderer::default().render_heatmap(occupancy);
+
+            for y in 0..4 {
+                for x in 0..4 {
+                    let center = [(x as f32 + 0.5) / 4.0, (y as f32 + 0.5) / 4.0];
+                    let expected = heat(occupancy.get(x, y) / occupancy.max());
+
+                    assert_eq!(pixel_at(&canvas, center), expected);
+                }
+            }
+
+            let hottest = (0..16).filter(|&idx| occupancy.values()[idx] == occupancy.max()).count();
+            let white = canvas.as_rgb().chunks(3).filter(|pixel| pixel == &[255, 255, 255]).count();
+
+            assert_eq!(white, hottest * 100 * 100);
+        }
+
+        #[test]
+        fn empty() {
+            let canvas = Renderer::default().render_heatmap(&sim::Heatmap::new(4));
+            assert_eq!(pixel_at(&canvas, [0.5, 0.5]), BACKGROUND);
+        }
+    }
+
+    mod heat {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(heat(0.0), BACKGROUND);
+            assert_eq!(heat(1.0 / 3.0), HEAT[1]);
+            assert_eq!(heat(0.5), HEAT[1].mix(HEAT[2], 0.5));
+            assert_eq!(heat(1.0), Color::rgb(255, 255, 255));
+            assert_eq!(heat(2.0), heat(1.0));
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::*;
+
+/// Statistics of a single generation, as logged
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record {
+    pub generation: usize,
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    pub caught: usize,
+    pub starved: usize,
+    pub avg_energy: f32,
+    pub avg_energy_spent: f32,
+    /// Average fitness of predators, if there are any
+    pub predators_avg_fitness: Option<f32>,
+}
+
+impl Record {
+    pub fn new(generation: usize, stats: &sim::Statistics) -> Self {
+        return Self {
+            generation,
+            min_fitness: stats.min_fitness(),
+            max_fitness: stats.max_fitness(),
+            avg_fitness: stats.avg_fitness(),
+            caught: stats.caught(),
+            starved: stats.starved(),
+            avg_energy: stats.avg_energy(),
+            avg_energy_spent: stats.avg_energy_spent(),
+            predators_avg_fitness: stats.predators().map(|predators| predators.avg_fitness()),
+        };
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "generation {}: min={:.2} max={:.2} avg={:.2} caught={} starved={} energy={:.2}",
+            self.generation,
+            self.min_fitness,
+            self.max_fitness,
+            self.avg_fitness,
+            self.caught,
+            self.starved,
+            self.avg_energy,
+        )?;
+
+        if let Some(predators) = self.predators_avg_fitness {
+            write!(f, " predators={predators:.2}")?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// All the places records go to
+pub struct Logs {
+    /// Whether records don't get printed
+    pub quiet: bool,
+    pub csv: Option<CsvLog<BufWriter<File>>>,
+    pub json: Option<JsonLog<BufWriter<File>>>,
+}
+
+impl Logs {
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        if !self.quiet {
+            println!("{record}");
+        }
+
+        if let Some(csv) = &mut self.csv {
+            csv.write(record)?;
+        }
+
+        if let Some(json) = &mut self.json {
+            json.write(record)?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// Writes records as CSV, one row per generation
+pub struct CsvLog<W> {
+    writer: W,
+}
+
+impl<W> CsvLog<W>
+    where
+        W: Write
+{
+    const HEADER: &'static str = "generation,min_fitness,max_fitness,avg_fitness,caught,starved,\
+                                  avg_energy,avg_energy_spent,predators_avg_fitness";
+
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "{}", Self::HEADER)?;
+        return Ok(Self { writer });
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let predators = record
+            .predators_avg_fitness
+            .map(|fitness| fitness.to_string())
+            .unwrap_or_default();
+
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{},{},{}",
+            record.generation,
+            record.min_fitness,
+            record.max_fitness,
+            record.avg_fitness,
+            record.caught,
+            record.starved,
+            record.avg_energy,
+            record.avg_energy_spent,
+            predators,
+        )?;
+
+        // Logs are flushed as we go, so that they can be watched while the simulation runs
+        return self.writer.flush();
+    }
+}
+
+/// Writes records (or anything else, e.g. events) as JSON Lines, one object per line
+pub struct JsonLog<W> {
+    writer: W,
+}
+
+impl<W> JsonLog<W>
+    where
+        W: Write
+{
+    pub fn new(writer: W) -> Self {
+        return Self { writer };
+    }
+
+    pub fn write(&mut self, record: &impl Serialize) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        writeln!(self.writer)?;
+        return self.writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(predators_avg_fitness: Option<f32>) -> Record {
+        return Record {
+            generation: 3,
+            min_fitness: 0.0,
+            max_fitness: 5.0,
+            avg_fitness: 1.5,
+            caught: 2,
+            starved: 0,
+            avg_energy: 0.75,
+            avg_energy_spent: 0.25,
+            predators_avg_fitness,
+        };
+    }
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn display() {
+            assert_eq!(
+                record(None).to_string(),
+                "generation 3: min=0.00 max=5.00 avg=1.50 caught=2 starved=0 energy=0.75",
+            );
+
+            assert!(record(Some(2.0)).to_string().ends_with(" predators=2.00"));
+        }
+    }
+
+    mod csv_log {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut log = CsvLog::new(Vec::new()).unwrap();
+            log.write(&record(None)).unwrap();
+            log.write(&record(Some(2.5))).unwrap();
+
+            let csv = String::from_utf8(log.writer).unwrap();
+            let lines: Vec<_> = csv.lines().collect();
+
+            assert_eq!(lines.len(), 3);
+            assert!(lines[0].starts_with("generation,min_fitness,"));
+            assert_eq!(lines[1], "3,0,5,1.5,2,0,0.75,0.25,");
+            assert_eq!(lines[2], "3,0,5,1.5,2,0,0.75,0.25,2.5");
+            assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        }
+    }
+
+    mod json_log {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut log = JsonLog::new(Vec::new());
+            log.write(&record(None)).unwrap();
+            log.write(&record(Some(2.5))).unwrap();
+
+            let json = String::from_utf8(log.writer).unwrap();
+            let lines: Vec<serde_json::Value> = json
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+
+            assert_eq!(lines.len(), 2);
+            assert_eq!(lines[0]["max_fitness"], 5.0);
+            assert_eq!(lines[0]["predators_avg_fitness"], serde_json::Value::Null);
+            assert_eq!(lines[1]["predators_avg_fitness"], 2.5);
+        }
+    }
+}
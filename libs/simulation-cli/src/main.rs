@@ -0,0 +1,308 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use clap::Parser;
+use rand::prelude::*;
+use serde::Serialize;
+
+use lib_renderer::{Canvas, Renderer};
+use lib_simulation as sim;
+
+use self::{log::*, output::*, render::*};
+
+mod log;
+mod output;
+mod render;
+
+/// Resolution of heatmaps saved by `--heatmaps`, unless the config says otherwise
+const HEATMAP_GRID: usize = 32;
+
+/// Number of the last generation that has ended, along with its statistics
+type Last = (usize, sim::Statistics);
+
+/// Evolves birds headlessly, at full speed
+#[derive(Debug, Parser)]
+#[command(name = "shorelark-cli")]
+struct Args {
+    /// Simulation config, as TOML or JSON (picked by the file's extension); defaults to the
+    /// default config
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Number of generations to run
+    #[arg(short, long, default_value_t = 100)]
+    generations: usize,
+
+    /// Seed of the simulation; picked at random (and printed) if not given
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Writes statistics of each generation to this file, as CSV
+    #[arg(long)]
+    csv: Option<PathBuf>,
+
+    /// Writes statistics of each generation to this file, as JSON Lines
+    #[arg(long)]
+    json: Option<PathBuf>,
+
+    /// Writes events (e.g. foods eaten or deaths) to this file, as JSON Lines
+    #[arg(long, conflicts_with = "replay")]
+    events: Option<PathBuf>,
+
+    /// Saves heatmaps of where birds have flown and eaten during the last generation into this
+    /// directory, as CSV and PNG (enabling them with a default resolution, unless the config
+    /// sets `heatmap_grid`)
+    #[arg(long)]
+    heatmaps: Option<PathBuf>,
+
+    /// Saves the config, the final population and the best brain into this directory
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+
+    /// Records the run into this file, so that it can be replayed later (see `--replay`)
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Instead of running a new simulation, replays a recorded one (see `--record`) - either
+    /// entirely or up to `--frame`
+    #[arg(long, conflicts_with_all = ["config", "generations", "seed"])]
+    replay: Option<PathBuf>,
+
+    /// Frame (i.e. step) to stop replaying at
+    #[arg(long, requires = "replay")]
+    frame: Option<usize>,
+
+    /// Renders the final world into this PNG file
+    #[arg(long)]
+    png: Option<PathBuf>,
+
+    /// Animates the last generation (or, when replaying, the generation that `--frame` is in)
+    /// into this GIF file
+    #[arg(long)]
+    gif: Option<PathBuf>,
+
+    /// Takes a frame of the GIF only every this many steps
+    #[arg(long, default_value_t = 4, requires = "gif")]
+    gif_every: usize,
+
+    /// Width and height of the rendered images, in pixels
+    #[arg(long, default_value_t = 400)]
+    size: u16,
+
+    /// Draws the animals' fields of view
+    #[arg(long)]
+    vision: bool,
+
+    /// Doesn't print statistics of each generation
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let mut logs = Logs {
+        quiet: args.quiet,
+        csv: args.csv.as_deref().map(create).transpose()?.map(CsvLog::new).transpose()?,
+        json: args.json.as_deref().map(create).transpose()?.map(JsonLog::new),
+    };
+
+    let renderer = Renderer {
+        width: args.size.into(),
+        height: args.size.into(),
+        vision: args.vision,
+    };
+
+    let gif = args
+        .gif
+        .as_deref()
+        .map(|path| Gif::create(path, renderer.clone(), args.gif_every))
+        .transpose()?;
+
+    let started_at = Instant::now();
+
+    let (seed, sim, last) = match &args.replay {
+        Some(path) => replay(path, args.frame, &mut logs, gif)?,
+        None => run(&args, &mut logs, gif)?,
+    };
+
+    eprintln!("done in {:.2?}", started_at.elapsed());
+
+    if let Some(path) = &args.png {
+        save_png(path, &renderer.render(sim.world()))?;
+    }
+
+    if let Some(dir) = &args.heatmaps {
+        let heatmaps = last
+            .as_ref()
+            .and_then(|(_, stats)| Some([("occupancy", stats.occupancy()?), ("feeding", stats.feeding()?)]))
+            .ok_or("no heatmaps to save - either no generation has ended, or the replayed config doesn't set `heatmap_grid`")?;
+
+        fs::create_dir_all(dir)?;
+
+        for (name, heatmap) in heatmaps {
+            write_heatmap(heatmap, create(&dir.join(format!("{name}.csv")))?)?;
+            save_png(&dir.join(format!("{name}.png")), &renderer.render_heatmap(heatmap))?;
+        }
+    }
+
+    if let Some(dir) = &args.out {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("config.toml"), sim.config().to_toml())?;
+
+        let population = Population::new(seed, &sim);
+        save_json(&dir.join("population.json"), &population)?;
+
+        if let Some((generation, stats)) = &last {
+            save_json(&dir.join("best.json"), &Best::new(*generation, stats))?;
+        }
+    }
+
+    return Ok(());
+}
+
+/// Runs a new simulation for `args.generations` generations
+fn run(
+    args: &Args,
+    logs: &mut Logs,
+    mut gif: Option<Gif>,
+) -> Result<(u64, sim::Simulation, Option<Last>), Box<dyn Error>> {
+    let mut config = match &args.config {
+        Some(path) => load_config(path)?,
+        None => sim::SimulationConfig::default(),
+    };
+
+    if args.heatmaps.is_some() && config.heatmap_grid == 0 {
+        config.heatmap_grid = HEATMAP_GRID;
+    }
+
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+    let mut sim = sim::Simulation::from_seed(seed, config).map_err(|err| format!("invalid config: {err}"))?;
+
+    eprintln!("seed: {seed}");
+
+    if args.record.is_some() {
+        sim.start_recording();
+    }
+
+    let mut events = args.events.as_deref().map(create).transpose()?.map(JsonLog::new);
+
+    if events.is_some() {
+        sim.simulation_mut().collect_events(true);
+    }
+
+    let mut last = None;
+
+    for generation in 1..=args.generations {
+        let stats = match &mut gif {
+            // (the last generation gets animated step by step)
+            Some(gif) if generation == args.generations => loop {
+                let stats = sim.step(None);
+                gif.step(sim.world())?;
+
+                if let Some(stats) = stats {
+                    break stats;
+                }
+            },
+
+            _ => sim.train(None),
+        };
+
+        logs.write(&Record::new(generation, &stats))?;
+        last = Some((generation, stats));
+
+        if let Some(events) = &mut events {
+            for event in sim.simulation_mut().drain_events() {
+                events.write(&event)?;
+            }
+        }
+    }
+
+    if let (Some(path), Some(recorder)) = (&args.record, sim.stop_recording()) {
+        fs::write(path, recorder.to_json())?;
+    }
+
+    if let Some(gif) = gif {
+        gif.finish()?;
+    }
+
+    let (sim, _) = sim.into_parts();
+    return Ok((seed, sim, last));
+}
+
+/// Replays a recorded simulation, up to `frame`
+fn replay(
+    path: &Path,
+    frame: Option<usize>,
+    logs: &mut Logs,
+    gif: Option<Gif>,
+) -> Result<(u64, sim::Simulation, Option<Last>), Box<dyn Error>> {
+    let mut replay = sim::Replay::from_json(&fs::read_to_string(path)?)?;
+    let frame = frame.unwrap_or(replay.len()).min(replay.len());
+
+    eprintln!("seed: {}, frames: {}", replay.seed(), replay.len());
+
+    let mut generation = 0;
+    let mut generation_starts = [0, 0];
+    let mut last = None;
+
+    while replay.position() < frame {
+        if let Some(stats) = replay.step_forward() {
+            generation += 1;
+            generation_starts = [generation_starts[1], replay.position()];
+
+            logs.write(&Record::new(generation, &stats))?;
+            last = Some((generation, stats));
+        }
+    }
+
+    if let Some(mut gif) = gif {
+        // Rewinding is cheap, so the frames get rendered only now that it's known where the
+        // generation starts (if `frame` ends a generation, that's the one to animate)
+        let [previous, current] = generation_starts;
+        replay.seek(if current == frame { previous } else { current });
+
+        while replay.position() < frame {
+            replay.step_forward();
+            gif.step(replay.world())?;
+        }
+
+        gif.finish()?;
+    }
+
+    return Ok((replay.seed(), replay.into_simulation(), last));
+}
+
+fn load_config(path: &Path) -> Result<sim::SimulationConfig, Box<dyn Error>> {
+    let config = fs::read_to_string(path)?;
+
+    return match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => Ok(sim::SimulationConfig::from_json(&config)?),
+        _ => Ok(sim::SimulationConfig::from_toml(&config)?),
+    };
+}
+
+fn create(path: &Path) -> std::io::Result<BufWriter<File>> {
+    return File::create(path).map(BufWriter::new);
+}
+
+fn save_json(path: &Path, value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    let mut writer = create(path)?;
+
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.flush()?;
+
+    return Ok(());
+}
+
+fn save_png(path: &Path, canvas: &Canvas) -> Result<(), Box<dyn Error>> {
+    let mut writer = create(path)?;
+
+    canvas.write_png(&mut writer)?;
+    writer.flush()?;
+
+    return Ok(());
+}
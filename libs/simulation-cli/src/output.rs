@@ -0,0 +1,151 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::*;
+
+/// Everyone living in the world at the end of the run
+#[derive(Debug, Serialize)]
+pub struct Population {
+    pub seed: u64,
+    pub animals: Vec<Animal>,
+}
+
+impl Population {
+    pub fn new(seed: u64, sim: &sim::Simulation) -> Self {
+        let config = sim.config();
+
+        let animals = sim
+            .world()
+            .animals()
+            .iter()
+            .chain(sim.world().predators())
+            .map(|animal| Animal::new(animal, config))
+            .collect();
+
+        return Self { seed, animals };
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Animal {
+    pub species: sim::Species,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    /// Brain, followed by genes of the eye and of the body (if they evolve - see the config)
+    pub chromosome: Vec<f32>,
+}
+
+impl Animal {
+    pub fn new(animal: &sim::Animal, config: &sim::SimulationConfig) -> Self {
+        return Self {
+            species: animal.species(),
+            x: animal.position().x,
+            y: animal.position().y,
+            rotation: animal.rotation().angle(),
+            chromosome: animal.as_chromosome(config).iter().copied().collect(),
+        };
+    }
+}
+
+/// The fittest bird of the last generation
+#[derive(Debug, Serialize)]
+pub struct Best {
+    pub generation: usize,
+    pub fitness: f32,
+    /// Brain, followed by genes of the eye and of the body (if they evolve - see the config)
+    pub chromosome: Vec<f32>,
+}
+
+impl Best {
+    pub fn new(generation: usize, stats: &sim::Statistics) -> Self {
+        return Self {
+            generation,
+            fitness: stats.max_fitness(),
+            chromosome: stats.fittest().iter().copied().collect(),
+        };
+    }
+}
+
+/// Writes the heatmap as CSV, one row of cells per line (starting with the row at `y = 0`)
+pub fn write_heatmap(heatmap: &sim::Heatmap, mut writer: impl Write) -> io::Result<()> {
+    for row in heatmap.values().chunks(heatmap.size()) {
+        let row: Vec<_> = row.iter().map(f32::to_string).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    return writer.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod population {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = sim::SimulationConfig {
+                animals: 5,
+                predators: 2,
+                ..sim::SimulationConfig::default()
+            };
+
+            let sim = sim::Simulation::from_seed(1, config).unwrap();
+            let population = Population::new(sim.seed(), sim.simulation());
+
+            assert_eq!(population.seed, 1);
+            assert_eq!(population.animals.len(), 7);
+            assert_eq!(population.animals[6].species, sim::Species::Predator);
+
+            let json = serde_json::to_value(&population).unwrap();
+            assert_eq!(json["animals"][0]["species"], "prey");
+            assert!(!json["animals"][0]["chromosome"].as_array().unwrap().is_empty());
+        }
+    }
+
+    mod best {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut sim = sim::Simulation::from_seed(1, sim::SimulationConfig::default()).unwrap();
+            let stats = sim.train(Some(50));
+            let best = Best::new(1, &stats);
+
+            assert_eq!(best.fitness, stats.max_fitness());
+            assert_eq!(best.chromosome.len(), sim.world().animals()[0].as_chromosome(sim.simulation().config()).len());
+        }
+    }
+
+    mod write_heatmap {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = sim::SimulationConfig {
+                heatmap_grid: 3,
+                ..sim::SimulationConfig::default()
+            };
+
+            let mut sim = sim::Simulation::from_seed(1, config).unwrap();
+            let stats = sim.train(Some(50));
+            let occupancy = stats.occupancy().unwrap();
+
+            let mut csv = Vec::new();
+            write_heatmap(occupancy, &mut csv).unwrap();
+
+            let csv = String::from_utf8(csv).unwrap();
+            let values: Vec<Vec<f32>> = csv
+                .lines()
+                .map(|line| line.split(',').map(|value| value.parse().unwrap()).collect())
+                .collect();
+
+            assert_eq!(values.len(), 3);
+            assert_eq!(values[2][1], occupancy.get(1, 2));
+            assert_eq!(values.concat(), occupancy.values());
+        }
+    }
+}
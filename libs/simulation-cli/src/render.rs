@@ -0,0 +1,56 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use lib_renderer::{Animation, Renderer};
+
+use crate::*;
+
+const FPS: u16 = 25;
+
+/// Animates steps of the simulation into a GIF (see `--gif`)
+pub struct Gif {
+    renderer: Renderer,
+    animation: Animation<BufWriter<File>>,
+
+    /// Every how many steps a frame gets taken
+    every: usize,
+    steps: usize,
+}
+
+impl Gif {
+    pub fn create(path: &Path, renderer: Renderer, every: usize) -> Result<Self, Box<dyn Error>> {
+        let animation = Animation::new(
+            create(path)?,
+            u16::try_from(renderer.width)?,
+            u16::try_from(renderer.height)?,
+            FPS,
+        )?;
+
+        return Ok(Self {
+            renderer,
+            animation,
+            every: every.max(1),
+            steps: 0,
+        });
+    }
+
+    /// Called after each step of the simulation
+    pub fn step(&mut self, world: &sim::World) -> Result<(), Box<dyn Error>> {
+        if self.steps.is_multiple_of(self.every) {
+            self.animation.add_frame(&self.renderer.render(world))?;
+        }
+
+        self.steps += 1;
+
+        return Ok(());
+    }
+
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        let mut writer = self.animation.finish()?;
+        writer.flush()?;
+
+        return Ok(());
+    }
+}
@@ -2,17 +2,34 @@ use crate::*;
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Animal {
+    pub species: sim::Species,
     pub x: f32,
     pub y: f32,
     pub rotation: f32,
+    pub energy: f32,
+    pub alive: bool,
+    pub radius: f32,
+    pub speed_max: f32,
+    pub rotation_accel: f32,
+    /// Hue in <0, 1>, present only when bodies evolve
+    pub color: Option<f32>,
+    pub telemetry: sim::Telemetry,
 }
 
 impl From<&sim::Animal> for Animal {
     fn from(animal: &sim::Animal) -> Self {
         return Self {
+            species: animal.species(),
             x: animal.position().x,
             y: animal.position().y,
             rotation: animal.rotation().angle(),
+            energy: animal.energy(),
+            alive: animal.is_alive(),
+            radius: animal.body().radius(),
+            speed_max: animal.body().speed_max(),
+            rotation_accel: animal.body().rotation_accel(),
+            color: animal.body().color(),
+            telemetry: animal.telemetry(),
         };
     }
 }
@@ -3,7 +3,8 @@ use crate::*;
 #[derive(Clone, Debug, Serialize)]
 pub struct Food {
     pub x: f32,
-    pub y: f32
+    pub y: f32,
+    pub nutrition: f32,
 }
 
 impl From<&sim::Food> for Food {
@@ -11,6 +12,7 @@ impl From<&sim::Food> for Food {
         return Self {
             x: food.position().x,
             y: food.position().y,
+            nutrition: food.nutrition(),
         }
     }
 }
\ No newline at end of file
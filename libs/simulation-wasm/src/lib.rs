@@ -7,29 +7,103 @@ use lib_simulation as sim;
 pub use crate::{
     animal::*,
     food::*,
+    replay::*,
     statistics::*,
     world::*,
 };
 
 mod animal;
 mod food;
+mod replay;
 mod statistics;
 mod world;
 
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
-    sim: sim::Simulation,
+    sim: sim::SeededSimulation,
 }
 
 #[wasm_bindgen]
 impl Simulation {
+    /// Creates a simulation from a (possibly partial) config object, e.g.
+    /// `new Simulation({ animals: 100 })`; `new Simulation()` uses the default config.
+    ///
+    /// The seed is picked at random - see `seed()` to replay the run later.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let mut rng = thread_rng();
-        let sim = sim::Simulation::random(&mut rng);
+    pub fn new(config: JsValue) -> Result<Simulation, JsValue> {
+        return Self::from_seed(thread_rng().gen(), config);
+    }
+
+    /// Creates a simulation that always evolves the same way given the same seed and config
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: u64, config: JsValue) -> Result<Simulation, JsValue> {
+        let config: Option<sim::SimulationConfig> = serde_wasm_bindgen::from_value(config)?;
+        let sim = sim::Simulation::from_seed(seed, config.unwrap_or_default())
+            .map_err(|err| JsValue::from_str(&err))?;
+
+        return Ok(Self { sim });
+    }
+
+    /// Imports a simulation exported by `export()`, so that it can be continued exactly where
+    /// it was left off
+    #[wasm_bindgen(js_name = import)]
+    pub fn import(snapshot: &str) -> Result<Simulation, JsValue> {
+        let snapshot = sim::Snapshot::from_json(snapshot)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        let sim = sim::SeededSimulation::restore(snapshot).map_err(|err| JsValue::from_str(&err))?;
+
+        return Ok(Self { sim });
+    }
+
+    /// Exports the complete state of the simulation as a JSON blob (see `import()`)
+    pub fn export(&self) -> String {
+        return self.sim.snapshot().to_json();
+    }
+
+    /// Starts recording each step, so that the simulation can be replayed later (see `Replay`)
+    #[wasm_bindgen(js_name = startRecording)]
+    pub fn start_recording(&mut self) {
+        self.sim.start_recording();
+    }
+
+    /// Stops recording and returns the recording as a JSON blob, or `null` if the simulation
+    /// wasn't being recorded
+    #[wasm_bindgen(js_name = stopRecording)]
+    pub fn stop_recording(&mut self) -> Option<String> {
+        return self.sim.stop_recording().map(|recorder| recorder.to_json());
+    }
 
-        return Self { rng, sim };
+    #[wasm_bindgen(js_name = isRecording)]
+    pub fn is_recording(&self) -> bool {
+        return self.sim.recorder().is_some();
+    }
+
+    /// Starts (or stops) collecting events - see `drainEvents()`
+    #[wasm_bindgen(js_name = collectEvents)]
+    pub fn collect_events(&mut self, collect: bool) {
+        self.sim.simulation_mut().collect_events(collect);
+    }
+
+    /// Returns an array of events (e.g. `{ kind: 'ate', time, animal, food, position }`)
+    /// collected since the last call, oldest first
+    #[wasm_bindgen(js_name = drainEvents)]
+    pub fn drain_events(&mut self) -> JsValue {
+        let events: Vec<_> = self.sim.simulation_mut().drain_events().collect();
+        return serde_wasm_bindgen::to_value(&events).unwrap();
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.sim.seed();
+    }
+
+    pub fn config(&self) -> JsValue {
+        return serde_wasm_bindgen::to_value(self.sim.simulation().config()).unwrap();
+    }
+
+    #[wasm_bindgen(js_name = defaultConfig)]
+    pub fn default_config() -> JsValue {
+        return serde_wasm_bindgen::to_value(&sim::SimulationConfig::default()).unwrap();
     }
 
     pub fn world(&self) -> JsValue {
@@ -37,15 +111,27 @@ impl Simulation {
         return serde_wasm_bindgen::to_value(&world).unwrap();
     }
 
+    /// Where birds have been during the current generation so far, as a `Float32Array` of
+    /// `heatmap_grid`×`heatmap_grid` cells, row by row; `undefined` unless the config sets
+    /// `heatmap_grid`
+    pub fn occupancy(&self) -> Option<Vec<f32>> {
+        return self.sim.simulation().occupancy().map(|heatmap| heatmap.values().to_vec());
+    }
+
+    /// Same as `occupancy()`, but of where birds have eaten
+    pub fn feeding(&self) -> Option<Vec<f32>> {
+        return self.sim.simulation().feeding().map(|heatmap| heatmap.values().to_vec());
+    }
+
     pub fn step(&mut self) -> JsValue {
-        if let Some(statistics) = self.sim.step(&mut self.rng, None) {
+        if let Some(statistics) = self.sim.step(None) {
             return serde_wasm_bindgen::to_value(&Statistics::from(&statistics)).unwrap();
         }
         return JsValue::null();
     }
 
     pub fn train(&mut self, generation_length: Option<usize>) -> JsValue {
-        let stats = self.sim.train(&mut self.rng, generation_length);
+        let stats = self.sim.train(generation_length);
         return serde_wasm_bindgen::to_value(&Statistics::from(&stats)).unwrap();
     }
 }
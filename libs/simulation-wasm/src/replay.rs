@@ -0,0 +1,55 @@
+use crate::*;
+
+/// Plays back a recording made by `Simulation.stopRecording()`, frame by frame
+#[wasm_bindgen]
+pub struct Replay {
+    replay: sim::Replay,
+}
+
+#[wasm_bindgen]
+impl Replay {
+    #[wasm_bindgen(constructor)]
+    pub fn new(recording: &str) -> Result<Replay, JsValue> {
+        let replay = sim::Replay::from_json(recording)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        return Ok(Self { replay });
+    }
+
+    /// Number of steps - the last frame is at `len()`
+    pub fn len(&self) -> usize {
+        return self.replay.len();
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        return self.replay.is_empty();
+    }
+
+    pub fn position(&self) -> usize {
+        return self.replay.position();
+    }
+
+    pub fn world(&self) -> JsValue {
+        let world = World::from(self.replay.world());
+        return serde_wasm_bindgen::to_value(&world).unwrap();
+    }
+
+    /// Goes to the next frame, returning the statistics if a generation ended on the way
+    #[wasm_bindgen(js_name = stepForward)]
+    pub fn step_forward(&mut self) -> JsValue {
+        if let Some(statistics) = self.replay.step_forward() {
+            return serde_wasm_bindgen::to_value(&Statistics::from(&statistics)).unwrap();
+        }
+        return JsValue::null();
+    }
+
+    #[wasm_bindgen(js_name = stepBackward)]
+    pub fn step_backward(&mut self) {
+        self.replay.step_backward();
+    }
+
+    pub fn seek(&mut self, position: usize) {
+        self.replay.seek(position);
+    }
+}
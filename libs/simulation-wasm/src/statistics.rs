@@ -6,14 +6,39 @@ pub struct Statistics {
     pub min: f32,
     pub max: f32,
     pub avg: f32,
+    pub caught: usize,
+    pub starved: usize,
+    pub avg_energy: f32,
+
+    /// Fitness of predators - `undefined` if there are none
+    pub predators_min: Option<f32>,
+    pub predators_max: Option<f32>,
+    pub predators_avg: Option<f32>,
+
+    /// Heatmaps of the generation (see `Simulation::occupancy()`) - `undefined` unless the
+    /// config sets `heatmap_grid`
+    #[wasm_bindgen(skip)]
+    pub occupancy: Option<Vec<f32>>,
+    #[wasm_bindgen(skip)]
+    pub feeding: Option<Vec<f32>>,
 }
 
 impl From<&sim::Statistics> for Statistics {
     fn from(statistics: &sim::Statistics) -> Self {
+        let predators = statistics.predators();
+
         return Self {
             min: statistics.min_fitness(),
             max: statistics.max_fitness(),
             avg: statistics.avg_fitness(),
+            caught: statistics.caught(),
+            starved: statistics.starved(),
+            avg_energy: statistics.avg_energy(),
+            predators_min: predators.map(|predators| predators.min_fitness()),
+            predators_max: predators.map(|predators| predators.max_fitness()),
+            predators_avg: predators.map(|predators| predators.avg_fitness()),
+            occupancy: statistics.occupancy().map(|heatmap| heatmap.values().to_vec()),
+            feeding: statistics.feeding().map(|heatmap| heatmap.values().to_vec()),
         };
     }
 }
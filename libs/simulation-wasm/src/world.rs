@@ -2,8 +2,10 @@ use crate::*;
 
 #[derive(Clone, Debug, Serialize)]
 pub struct World {
+    /// Birds and predators alike - see `Animal::species`
     pub animals: Vec<Animal>,
     pub foods: Vec<Food>,
+    pub obstacles: Vec<sim::Obstacle>,
 }
 
 
@@ -12,13 +14,17 @@ impl From<&sim::World> for World {
         let animals = world
             .animals()
             .iter()
+            .chain(world.predators())
             .map(Animal::from)
             .collect();
         let foods = world
             .foods()
             .iter()
+            .filter(|food| food.is_available())
             .map(Food::from)
             .collect();
-        return Self { animals, foods };
+        let obstacles = world.obstacles().to_vec();
+
+        return Self { animals, foods, obstacles };
     }
 }
@@ -1,54 +1,168 @@
 use crate::*;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Species {
+    /// Bird, eating food
+    Prey,
 
-#[derive(Debug)]
+    /// Eating birds
+    Predator,
+}
+
+/// Counters describing how an animal has lived so far (see `Animal::telemetry()`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Telemetry {
+    /// Total distance flown
+    pub distance: f32,
+    /// Average distance flown per step
+    pub avg_speed: f32,
+    /// Total angle turned, regardless of the direction
+    pub turned: f32,
+    /// Number of foods (or, for predators, birds) eaten
+    pub foods: usize,
+    /// Number of steps since the animal has last eaten (or since it's been born)
+    pub since_meal: usize,
+    /// Total, including energy that couldn't be spent due to starvation
+    pub energy_spent: f32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Animal {
-    pub(crate) position: na::Point2<f32>,
-    pub(crate) rotation: na::Rotation2<f32>,
-    pub(crate) speed: f32,
+    pub (crate) species: Species,
+    pub (crate) position: na::Point2<f32>,
+    pub (crate) rotation: na::Rotation2<f32>,
+    pub (crate) speed: f32,
 
     pub (crate) eye: Eye,
     pub (crate) brain: Brain,
+    pub (crate) body: Body,
 
     pub (crate) satiation: usize, // Number of foods eaten
+    pub (crate) age: usize, // Number of steps lived
+    pub (crate) distance: f32, // Total distance flown
+    pub (crate) turned: f32, // Total angle turned, regardless of the direction
+    pub (crate) offspring: usize, // Number of children born in the open-ended mode
+    pub (crate) last_meal: usize, // Age at which the animal has last eaten
+
+    pub (crate) energy: f32,
+    pub (crate) energy_spent: f32, // Total, including energy that couldn't be spent due to starvation
+    pub (crate) alive: bool,
+    pub (crate) caught: bool, // Whether a predator has caught this bird
 }
 
 impl Animal {
-
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    fn new(
+        species: Species,
+        eye: Eye,
+        brain: Brain,
+        body: Body,
+        rng: &mut dyn RngCore,
+        config: &SimulationConfig,
+    ) -> Self {
         Self {
-            position: rng.gen(),
-            rotation: rng.gen(),
+            species,
+            position: world::random_position(rng, &config.obstacles),
+            rotation: trig::random_rotation(rng),
             speed: 0.002,
             eye,
             brain,
+            body,
             satiation: 0,
+            age: 0,
+            distance: 0.0,
+            turned: 0.0,
+            offspring: 0,
+            last_meal: 0,
+            energy: config.energy_max,
+            energy_spent: 0.0,
+            alive: true,
+            caught: false,
         }
     }
 
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
+    pub fn random(rng: &mut dyn RngCore, config: &SimulationConfig) -> Self {
+        return Self::random_of(Species::Prey, rng, config);
+    }
+
+    pub fn random_of(species: Species, rng: &mut dyn RngCore, config: &SimulationConfig) -> Self {
+        let eye = if config.evolve_eye {
+            config.eye_from_genes(species, [rng.gen(), rng.gen()])
+        } else {
+            config.eye(species)
+        };
+
         let brain = Brain::random(rng, &eye);
 
-        return Self {
-            position: rng.gen(),
-            rotation: rng.gen(),
-            speed: 0.002,
-            eye,
-            brain,
-            satiation: 0
+        let body = if config.evolve_body {
+            config.body_from_genes([rng.gen(), rng.gen(), rng.gen(), rng.gen()])
+        } else {
+            config.body()
         };
+
+        return Self::new(species, eye, brain, body, rng, config);
+    }
+
+    /// Genes this animal was born from, e.g. to save it to disk; `config` must be the one the
+    /// animal lives by
+    pub fn as_chromosome(&self, config: &SimulationConfig) -> ga::Chromosome {
+        // The brain comes first, followed by genes of the eye and then of the body (if they
+        // evolve at all)
+        let eye = config.evolve_eye.then(|| self.eye.as_genes(config));
+        let body = config.evolve_body.then(|| self.body.as_genes(config));
+
+        return self
+            .brain
+            .as_chromosome()
+            .into_iter()
+            .chain(eye.into_iter().flatten())
+            .chain(body.into_iter().flatten())
+            .collect();
     }
 
-    pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        // We evolve only our birds' brains, but technically there's no
-        // reason not to simulate e.g. physical properties such as size.
-        //
-        // If that was to happen, this function could be adjusted to
-        // return a longer chromosome that encodes not only the brain,
-        // but also, say, birdie's color.
+    pub fn eye(&self) -> &Eye {
+        return &self.eye;
+    }
 
-        return self.brain.as_chromosome();
+    pub fn body(&self) -> &Body {
+        return &self.body;
+    }
+
+    /// Default behavior descriptor used by novelty search, with every component in <0, 1>:
+    /// - the center of the `behavior_grid`×`behavior_grid` cell the bird is in right now,
+    /// - its average speed (0.0 = `speed_min`, 1.0 = `speed_max`).
+    pub fn behavior(&self, config: &SimulationConfig) -> Vec<f32> {
+        let cell = |coord: f32| {
+            let grid = config.behavior_grid as f32;
+            return ((coord * grid).floor().min(grid - 1.0) + 0.5) / grid;
+        };
+
+        return vec![cell(self.position.x), cell(self.position.y), self.avg_speed(config)];
+    }
+
+    /// Behavior descriptor used by `Simulation::random_flight_styles()`, with every component
+    /// in <0, 1>:
+    /// - average speed (0.0 = `speed_min`, 1.0 = `speed_max`),
+    /// - average turn rate (0.0 = flies straight, 1.0 = turns by `rotation_accel` each step).
+    pub fn flight_style(&self, config: &SimulationConfig) -> Vec<f32> {
+        let avg_turn_rate = self.turned / (self.age.max(1) as f32) / config.rotation_accel;
+
+        return vec![self.avg_speed(config), avg_turn_rate.clamp(0.0, 1.0)];
+    }
+
+    /// Average speed, scaled so that 0.0 = `speed_min` and 1.0 = `speed_max` (the config's one,
+    /// so that evolved bodies remain comparable)
+    fn avg_speed(&self, config: &SimulationConfig) -> f32 {
+        let avg_speed = self.distance / (self.age.max(1) as f32);
+        let speed_range = (config.speed_max - config.speed_min).max(f32::EPSILON);
+
+        return ((avg_speed - config.speed_min) / speed_range).clamp(0.0, 1.0);
     }
 
     pub fn position(&self) -> na::Point2<f32> {
@@ -59,10 +173,153 @@ impl Animal {
         return self.rotation;
     }
 
-    pub(crate) fn from_chromosome(chromosome: ga::Chromosome, rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_chromosome(chromosome, &eye);
+    pub fn species(&self) -> Species {
+        return self.species;
+    }
+
+    /// Number of steps lived
+    pub fn age(&self) -> usize {
+        return self.age;
+    }
+
+    pub fn energy(&self) -> f32 {
+        return self.energy;
+    }
+
+    pub fn is_alive(&self) -> bool {
+        return self.alive;
+    }
+
+    pub fn telemetry(&self) -> Telemetry {
+        return Telemetry {
+            distance: self.distance,
+            avg_speed: if self.age > 0 { self.distance / self.age as f32 } else { 0.0 },
+            turned: self.turned,
+            foods: self.satiation,
+            since_meal: self.age - self.last_meal,
+            energy_spent: self.energy_spent,
+        };
+    }
+
+    /// Number of foods (or, for predators, birds) eaten, minus the penalties for energy spent
+    /// (see `SimulationConfig::energy_fitness_weight`) and for getting caught (see
+    /// `SimulationConfig::caught_penalty`)
+    pub fn fitness(&self, config: &SimulationConfig) -> f32 {
+        let mut penalty = config.energy_fitness_weight * self.energy_spent / config.energy_max;
+
+        if self.caught {
+            penalty += config.caught_penalty;
+        }
+
+        return self.satiation as f32 - penalty;
+    }
+
+    /// Whether the bird is currently unable to fly (see `Starvation::Stop`)
+    pub (crate) fn is_exhausted(&self, config: &SimulationConfig) -> bool {
+        return config.starvation == Starvation::Stop && self.energy <= 0.0;
+    }
+
+    /// Eats food (or, for predators, a bird) worth `nutrition` times `food_energy`
+    pub (crate) fn eat(&mut self, nutrition: f32, config: &SimulationConfig) {
+        self.satiation += 1;
+        self.last_meal = self.age;
+        self.energy = (self.energy + nutrition * config.food_energy).min(config.energy_max);
+    }
+
+    /// Lets the brain decide where to fly next, given what the eye sees
+    pub (crate) fn process_brain(&mut self, vision: Vec<f32>, config: &SimulationConfig) {
+        let response = self.brain.nn.propagate(vision);
+        let speed_accel = config.speed_accel;
+        let rotation_accel = self.body.rotation_accel;
+
+        let speed_acceleration = response[0].clamp(-speed_accel, speed_accel);
+        let rotation_acceleration = response[1].clamp(-rotation_accel, rotation_accel);
+
+        self.speed = (self.speed + speed_acceleration).clamp(config.speed_min, self.body.speed_max);
+        self.rotation = trig::rotation(trig::angle(&self.rotation) + rotation_acceleration);
+        self.turned += rotation_acceleration.abs();
+        // rotation is already wrapped from [0, 2π]
+
+        let cost = config.acceleration_cost * speed_acceleration.abs()
+            + config.turn_cost * rotation_acceleration.abs();
+
+        self.spend_energy(cost, config);
+    }
+
+    /// Returns the index of the obstacle the animal has flown into, if any (the first one, if
+    /// there are many)
+    pub (crate) fn process_movement(&mut self, obstacles: &[Obstacle], config: &SimulationConfig) -> Option<usize> {
+        if !self.alive {
+            return None;
+        }
+
+        self.age += 1;
+
+        if self.is_exhausted(config) {
+            let cost = config.metabolism_cost + config.vision_cost * self.eye.area();
+            self.spend_energy(cost, config);
+            return None;
+        }
+
+        let mut position = self.position + self.rotation * na::Vector2::new(self.speed, 0.0);
+        let mut rotation = self.rotation;
+        let mut collided = None;
+
+        // (on a torus obstacles don't wrap around, so they better not touch the world's edges)
+        for (idx, obstacle) in obstacles.iter().enumerate() {
+            let (new_position, new_rotation) = obstacle.collide(self.position, position, rotation, config.obstacle_response);
+
+            if new_position != position {
+                collided = collided.or(Some(idx));
+            }
+
+            (position, rotation) = (new_position, new_rotation);
+        }
+
+        (self.position, self.rotation) = config.topology.constrain(position, rotation);
+        self.distance += self.speed;
+
+        let cost = config.metabolism_cost
+            + config.speed_cost * self.speed
+            + config.vision_cost * self.eye.area();
+        self.spend_energy(cost, config);
+
+        return collided;
+    }
+
+    pub (crate) fn spend_energy(&mut self, amount: f32, config: &SimulationConfig) {
+        self.energy = (self.energy - amount).max(0.0);
+        self.energy_spent += amount;
+
+        if self.energy <= 0.0 && config.starvation == Starvation::Die {
+            self.alive = false;
+        }
+    }
+
+    pub (crate) fn from_chromosome(
+        species: Species,
+        chromosome: ga::Chromosome,
+        rng: &mut dyn RngCore,
+        config: &SimulationConfig,
+    ) -> Self {
+        let mut genes: Vec<_> = chromosome.into_iter().collect();
+
+        let body = if config.evolve_body {
+            let body_genes = genes.split_off(genes.len() - BODY_GENES);
+            config.body_from_genes([body_genes[0], body_genes[1], body_genes[2], body_genes[3]])
+        } else {
+            config.body()
+        };
+
+        let eye = if config.evolve_eye {
+            let eye_genes = genes.split_off(genes.len() - EYE_GENES);
+            config.eye_from_genes(species, [eye_genes[0], eye_genes[1]])
+        } else {
+            config.eye(species)
+        };
+
+        let brain = Brain::from_chromosome(genes.into_iter().collect(), &eye);
 
-        return Self::new(eye, brain, rng);
+        return Self::new(species, eye, brain, body, rng, config);
     }
 }
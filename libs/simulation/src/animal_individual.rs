@@ -3,24 +3,56 @@ use crate::*;
 pub struct AnimalIndividual {
     fitness: f32,
     chromosome: ga::Chromosome,
+    behavior: Vec<f32>,
 }
 
 impl AnimalIndividual {
-    pub fn from_animal(animal: &Animal) -> Self {
+    pub fn from_animal(animal: &Animal, config: &SimulationConfig) -> Self {
         return Self {
-            fitness: animal.satiation as f32,
-            chromosome: animal.as_chromosome()
+            fitness: animal.fitness(config),
+            chromosome: animal.as_chromosome(config),
+            behavior: Vec::new(),
         }
     }
 
-    pub fn into_animal(self, rng: &mut dyn RngCore) -> Animal {
-        return Animal::from_chromosome(self.chromosome, rng);
+    /// Like `from_animal()`, but measures fitness per `generation_length` steps lived, so that
+    /// animals of different ages can be compared fairly (as is the case during a steady-state
+    /// evolution).
+    pub fn from_animal_by_rate(animal: &Animal, config: &SimulationConfig) -> Self {
+        let rate = animal.fitness(config) / (animal.age.max(1) as f32);
+
+        return Self {
+            fitness: rate * (config.generation_length as f32),
+            chromosome: animal.as_chromosome(config),
+            behavior: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_fitness(&mut self, fitness: f32) {
+        self.fitness = fitness;
+    }
+
+    pub(crate) fn set_behavior(&mut self, behavior: Vec<f32>) {
+        self.behavior = behavior;
+    }
+
+    pub fn into_animal(self, rng: &mut dyn RngCore, config: &SimulationConfig) -> Animal {
+        return self.into_animal_of(Species::Prey, rng, config);
+    }
+
+    pub fn into_animal_of(
+        self,
+        species: Species,
+        rng: &mut dyn RngCore,
+        config: &SimulationConfig,
+    ) -> Animal {
+        return Animal::from_chromosome(species, self.chromosome, rng, config);
     }
 }
 
 impl ga::Individual for AnimalIndividual {
     fn create(chromosome: ga::Chromosome) -> Self {
-        return Self { fitness: 0.0, chromosome };
+        return Self { fitness: 0.0, chromosome, behavior: Vec::new() };
     }
 
     fn chromosome(&self) -> &ga::Chromosome {
@@ -30,4 +62,10 @@ impl ga::Individual for AnimalIndividual {
     fn fitness(&self) -> f32 {
         return self.fitness;
     }
-}
\ No newline at end of file
+}
+
+impl ga::BehavioralIndividual for AnimalIndividual {
+    fn behavior(&self) -> &[f32] {
+        return &self.behavior;
+    }
+}
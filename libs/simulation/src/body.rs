@@ -0,0 +1,80 @@
+use crate::*;
+
+/// Number of genes encoding a body, see `Body::as_genes()`
+pub(crate) const BODY_GENES: usize = 4;
+
+/// Physical traits of an animal
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Body {
+    /// How close food (or, for predators, a bird) must be to get eaten
+    pub(crate) radius: f32,
+
+    pub(crate) speed_max: f32,
+
+    /// How much the animal can turn in a single step
+    pub(crate) rotation_accel: f32,
+
+    /// Hue in <0, 1>, present only for evolved bodies (so that the UI can tell them apart)
+    pub(crate) color: Option<f32>,
+}
+
+impl Body {
+    pub fn radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    pub fn speed_max(&self) -> f32 {
+        return self.speed_max;
+    }
+
+    pub fn rotation_accel(&self) -> f32 {
+        return self.rotation_accel;
+    }
+
+    pub fn color(&self) -> Option<f32> {
+        return self.color;
+    }
+
+    /// Encodes the body as genes in <0, 1>, relative to the bounds set in `config` (see
+    /// `SimulationConfig::body_from_genes()` for the inverse)
+    pub(crate) fn as_genes(&self, config: &SimulationConfig) -> [f32; BODY_GENES] {
+        return [
+            to_gene(self.radius, config.radius_bounds),
+            to_gene(self.speed_max, config.speed_max_bounds),
+            to_gene(self.rotation_accel, config.rotation_accel_bounds),
+            self.color.unwrap_or_default(),
+        ];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn roundtrip() {
+        let config = SimulationConfig::default();
+        let body = config.body_from_genes([0.0, 0.5, 1.0, 0.75]);
+
+        assert_relative_eq!(body.radius(), config.radius_bounds[0]);
+        assert_relative_eq!(body.speed_max(), (config.speed_max_bounds[0] + config.speed_max_bounds[1]) / 2.0);
+        assert_relative_eq!(body.rotation_accel(), config.rotation_accel_bounds[1]);
+        assert_eq!(body.color(), Some(0.75));
+
+        let genes = body.as_genes(&config);
+        assert_relative_eq!(genes.as_slice(), [0.0, 0.5, 1.0, 0.75].as_slice());
+    }
+
+    #[test]
+    fn default() {
+        let config = SimulationConfig::default();
+        let body = config.body();
+
+        assert_relative_eq!(body.radius(), config.collision_radius);
+        assert_relative_eq!(body.speed_max(), config.speed_max);
+        assert_relative_eq!(body.rotation_accel(), config.rotation_accel);
+        assert_eq!(body.color(), None);
+    }
+}
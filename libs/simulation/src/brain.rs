@@ -1,6 +1,7 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Brain {
     pub(crate) nn: nn::Network,
 }
@@ -30,7 +31,7 @@ impl Brain {
             // network works on Vec<f32>, we can pass-through
             // numbers from eye into the neural network directly.
             nn::LayerTopology {
-                neurons: eye.cells(),
+                neurons: eye.outputs(),
             },
 
             // The Hidden Layer
@@ -44,7 +45,7 @@ impl Brain {
             // layer that has somewhat more neurons that the input
             // layer, and see how well the network performs.
             nn::LayerTopology {
-                neurons: 2 * eye.cells(),
+                neurons: 2 * eye.outputs(),
             },
 
             // The Output Layer
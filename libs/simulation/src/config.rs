@@ -0,0 +1,606 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8, PI, TAU};
+
+use crate::*;
+use crate::eye::{CELLS, FOV_ANGLE, FOV_RANGE};
+
+/// Everything that can be tweaked about a simulation without recompiling it.
+///
+/// Given the `serde` feature, configs can be loaded from TOML and JSON, where all fields are
+/// optional - missing ones keep their default values, so a config file only has to mention what
+/// it changes, e.g.:
+///
+/// ```toml
+/// animals = 100
+/// fov_range = 0.4
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SimulationConfig {
+    /// Non-zero speed prevents bird from being stuck in one place
+    pub speed_min: f32,
+
+    /// Prevent the bird from approaching the speed of causality `c`
+    pub speed_max: f32,
+
+    /// Prevent infinite accelerations in speed
+    pub speed_accel: f32,
+
+    /// And in rotation
+    pub rotation_accel: f32,
+
+    /// Minimum number of steps before evolving the algorithm
+    pub generation_length: usize,
+
+    /// Number of birds in a freshly created world
+    pub animals: usize,
+
+    /// Number of predators in a freshly created world (0 = no predators at all)
+    pub predators: usize,
+
+    /// Fitness a bird loses when it gets caught by a predator (which also kills it)
+    pub caught_penalty: f32,
+
+    /// Number of foods in a freshly created world
+    pub foods: usize,
+
+    /// Where, when and how nutritious food appears - at most one strategy of each kind (none =
+    /// eaten food instantly reappears at a random position)
+    pub food_spawners: Vec<FoodSpawner>,
+
+    /// What happens at the borders of the world
+    pub topology: Topology,
+
+    /// Energy a bird is born with, and the most it can store
+    pub energy_max: f32,
+
+    /// Energy restored by eating a single food
+    pub food_energy: f32,
+
+    /// Energy consumed each step just for being alive
+    pub metabolism_cost: f32,
+
+    /// Energy consumed per unit of distance flown
+    pub speed_cost: f32,
+
+    /// Energy consumed per unit of speed gained or lost
+    pub acceleration_cost: f32,
+
+    /// Energy consumed per radian turned
+    pub turn_cost: f32,
+
+    /// What happens to a bird that runs out of energy
+    pub starvation: Starvation,
+
+    /// How much wasting energy hurts a bird's fitness: each `energy_max` spent is worth this many
+    /// foods less (0.0 = fitness is just the number of foods eaten)
+    pub energy_fitness_weight: f32,
+
+    /// Static obstacles placed in the world
+    pub obstacles: Vec<Obstacle>,
+
+    /// What happens to animals flying into obstacles
+    pub obstacle_response: ObstacleResponse,
+
+    /// Open-ended mode (see `Simulation::step_open_ended()`): number of steps after which a
+    /// bird dies of old age
+    pub lifespan: usize,
+
+    /// Open-ended mode: number of foods a bird has to eat to give birth to a single offspring
+    pub foods_per_offspring: usize,
+
+    /// Open-ended mode: energy a bird passes on to each of its offspring, as a fraction of
+    /// `energy_max`; birds with less energy left can't reproduce
+    pub offspring_energy: f32,
+
+    /// Open-ended mode: how far from their parents offspring are born
+    pub offspring_distance: f32,
+
+    /// Open-ended mode: smallest and largest number of birds - when there's too few of them,
+    /// random birds fly in; when there's too many, no more get born
+    pub population_bounds: [usize; 2],
+
+    /// Birds eat food that's closer than this
+    pub collision_radius: f32,
+
+    /// Whether bodies (`collision_radius`, `speed_max`, `rotation_accel` and a colour) are
+    /// encoded in chromosomes and evolve together with brains (in which case the config's values
+    /// are ignored and animals are born with random bodies within the bounds below)
+    pub evolve_body: bool,
+
+    /// Smallest and largest `collision_radius` an evolved body can have
+    pub radius_bounds: [f32; 2],
+
+    /// Smallest and largest `speed_max` an evolved body can have
+    pub speed_max_bounds: [f32; 2],
+
+    /// Smallest and largest `rotation_accel` an evolved body can have
+    pub rotation_accel_bounds: [f32; 2],
+
+    /// How far the bird can see
+    /// - 0.1 = 10% of the map
+    /// - 0.5 = 50% of the map
+    /// - 1.0 = 100% of the map
+    pub fov_range: f32,
+
+    /// The range of vision the bird has, in radians
+    pub fov_angle: f32,
+
+    /// Number of photoreceptors in a single eye
+    pub cells: usize,
+
+    /// What birds see - each channel adds `cells` inputs to their brains
+    pub channels: Vec<Channel>,
+
+    /// Whether `fov_range` and `fov_angle` are encoded in chromosomes and evolve together with
+    /// brains (in which case the config's values are ignored and animals are born with random
+    /// eyes within the bounds below); `cells` stays fixed, since it determines the shape of the
+    /// brain
+    pub evolve_eye: bool,
+
+    /// Smallest and largest `fov_range` an evolved eye can have
+    pub fov_range_bounds: [f32; 2],
+
+    /// Smallest and largest `fov_angle` an evolved eye can have
+    pub fov_angle_bounds: [f32; 2],
+
+    /// Energy consumed each step per unit of area an eye can see (see `Eye::area()`)
+    pub vision_cost: f32,
+
+    /// `fov_range`, but for predators
+    pub predator_fov_range: f32,
+
+    /// `fov_angle`, but for predators
+    pub predator_fov_angle: f32,
+
+    /// `cells`, but for predators
+    pub predator_cells: usize,
+
+    /// `channels`, but for predators
+    pub predator_channels: Vec<Channel>,
+
+    /// Resolution of the position part of `Animal::behavior()`
+    pub behavior_grid: usize,
+
+    /// Resolution of heatmaps of where birds fly and eat (see `Statistics::occupancy()`), in
+    /// cells per side (0 = no heatmaps)
+    pub heatmap_grid: usize,
+
+    /// Probability of changing a gene while breeding (see `ga::GaussianMutation`)
+    pub mutation_chance: f32,
+
+    /// Magnitude of that change
+    pub mutation_coeff: f32,
+}
+
+#[cfg(feature = "serde")]
+impl SimulationConfig {
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        return toml::from_str(toml);
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        return serde_json::from_str(json);
+    }
+
+    pub fn to_toml(&self) -> String {
+        return toml::to_string(self).expect("config is always serializable");
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).expect("config is always serializable");
+    }
+}
+
+impl SimulationConfig {
+    pub(crate) fn eye(&self, species: Species) -> Eye {
+        return match species {
+            Species::Prey => {
+                Eye::new(self.fov_range, self.fov_angle, self.cells)
+                    .with_channels(self.channels.clone())
+            }
+
+            Species::Predator => {
+                Eye::new(self.predator_fov_range, self.predator_fov_angle, self.predator_cells)
+                    .with_channels(self.predator_channels.clone())
+            }
+        };
+    }
+
+    /// Same as `eye()`, but with `fov_range` and `fov_angle` decoded from genes (see
+    /// `Eye::as_genes()`)
+    pub(crate) fn eye_from_genes(&self, species: Species, genes: [f32; EYE_GENES]) -> Eye {
+        let mut eye = self.eye(species);
+
+        eye.fov_range = from_gene(genes[0], self.fov_range_bounds);
+        eye.fov_angle = from_gene(genes[1], self.fov_angle_bounds);
+
+        return eye;
+    }
+
+    /// Body shared by all animals whose bodies don't evolve
+    pub(crate) fn body(&self) -> Body {
+        return Body {
+            radius: self.collision_radius,
+            speed_max: self.speed_max,
+            rotation_accel: self.rotation_accel,
+            color: None,
+        };
+    }
+
+    /// Body decoded from genes (see `Body::as_genes()`)
+    pub(crate) fn body_from_genes(&self, genes: [f32; BODY_GENES]) -> Body {
+        return Body {
+            radius: from_gene(genes[0], self.radius_bounds),
+            speed_max: from_gene(genes[1], self.speed_max_bounds),
+            rotation_accel: from_gene(genes[2], self.rotation_accel_bounds),
+            color: Some(genes[3].rem_euclid(1.0)),
+        };
+    }
+
+    pub(crate) fn mutation(&self) -> ga::GaussianMutation {
+        return ga::GaussianMutation::new(self.mutation_chance, self.mutation_coeff);
+    }
+
+    pub(crate) fn genetic_algorithm(&self) -> DefaultOptimizer {
+        return ga::GeneticAlgorithm::new(
+            ga::RouletteWheelSelection::new(),
+            ga::UniformCrossover::new(),
+            self.mutation(),
+        );
+    }
+
+    /// Returns an error describing the first invalid setting, if there's any
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        ensure(self.speed_min > 0.0 && self.speed_min <= self.speed_max, "invalid speed range")?;
+        ensure(self.speed_accel >= 0.0, "negative speed_accel")?;
+        ensure(self.rotation_accel >= 0.0, "negative rotation_accel")?;
+        ensure(self.generation_length > 0, "generation_length must be positive")?;
+        ensure(self.animals > 0, "there must be at least one animal")?;
+        ensure(self.collision_radius >= 0.0, "negative collision_radius")?;
+        ensure(self.energy_max > 0.0, "energy_max must be positive")?;
+        ensure(self.food_energy >= 0.0, "negative food_energy")?;
+        ensure(self.metabolism_cost >= 0.0, "negative metabolism_cost")?;
+        ensure(self.speed_cost >= 0.0, "negative speed_cost")?;
+        ensure(self.acceleration_cost >= 0.0, "negative acceleration_cost")?;
+        ensure(self.turn_cost >= 0.0, "negative turn_cost")?;
+        ensure(self.vision_cost >= 0.0, "negative vision_cost")?;
+
+        for (fov_range, fov_angle, cells, channels) in [
+            (self.fov_range, self.fov_angle, self.cells, &self.channels),
+            (self.predator_fov_range, self.predator_fov_angle, self.predator_cells, &self.predator_channels),
+        ] {
+            ensure(fov_range > 0.0, "fov_range must be positive")?;
+            ensure(fov_angle > 0.0, "fov_angle must be positive")?;
+            ensure(cells > 0, "there must be at least one cell")?;
+            ensure(!channels.is_empty(), "there must be at least one channel")?;
+
+            for (idx, channel) in channels.iter().enumerate() {
+                if channels[..idx].contains(channel) {
+                    return Err(format!("duplicated channel: {channel:?}"));
+                }
+            }
+        }
+
+        for [min, max] in [self.fov_range_bounds, self.fov_angle_bounds] {
+            ensure(min > 0.0 && min <= max, "invalid eye bounds")?;
+        }
+
+        for [min, max] in [self.radius_bounds, self.speed_max_bounds, self.rotation_accel_bounds] {
+            ensure(min > 0.0 && min <= max, "invalid body bounds")?;
+        }
+
+        if self.evolve_body {
+            ensure(self.speed_max_bounds[0] >= self.speed_min, "invalid speed range")?;
+        }
+
+        for obstacle in &self.obstacles {
+            obstacle.validate()?;
+        }
+
+        ensure(world::has_room(&self.obstacles), "obstacles cover the entire world")?;
+
+        for (idx, spawner) in self.food_spawners.iter().enumerate() {
+            spawner.validate()?;
+            ensure(spawner.has_room(self), "obstacles cover all food patches")?;
+
+            let kind = std::mem::discriminant(spawner);
+
+            if self.food_spawners[..idx].iter().any(|other| std::mem::discriminant(other) == kind) {
+                return Err(format!("duplicated food spawner: {spawner:?}"));
+            }
+        }
+
+        ensure(self.lifespan > 0, "lifespan must be positive")?;
+        ensure(self.foods_per_offspring > 0, "foods_per_offspring must be positive")?;
+        ensure((0.0..=1.0).contains(&self.offspring_energy), "offspring_energy must be within <0, 1>")?;
+        ensure(self.offspring_distance >= 0.0, "negative offspring_distance")?;
+
+        let [min, max] = self.population_bounds;
+        ensure(min > 0 && min <= max, "invalid population bounds")?;
+
+        ensure(self.behavior_grid > 0, "behavior_grid must be positive")?;
+        ensure((0.0..=1.0).contains(&self.mutation_chance), "mutation_chance must be within <0, 1>")?;
+        ensure(self.mutation_coeff >= 0.0, "negative mutation_coeff")?;
+
+        return Ok(());
+    }
+}
+
+/// Fails with `error` unless `condition` holds
+pub(crate) fn ensure(condition: bool, error: &str) -> Result<(), String> {
+    return if condition { Ok(()) } else { Err(error.to_string()) };
+}
+
+/// What happens to a bird that runs out of energy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Starvation {
+    /// Nothing - energy is just tracked (and, optionally, affects fitness)
+    #[default]
+    Ignore,
+
+    /// The bird is too weak to fly or turn until it eats something (which it can only do if food
+    /// happens to spawn right on it)
+    Stop,
+
+    /// The bird dies - it stops moving, seeing and eating until the next generation, but it still
+    /// takes part in the evolution with whatever it had eaten
+    Die,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        return Self {
+            speed_min: 0.001,
+            speed_max: 0.005,
+            speed_accel: 0.2,
+            rotation_accel: FRAC_PI_2,
+            generation_length: 2500,
+            animals: 40,
+            predators: 0,
+            caught_penalty: 1.0,
+            foods: 60,
+            food_spawners: Vec::new(),
+            topology: Topology::Torus,
+            obstacles: Vec::new(),
+            obstacle_response: ObstacleResponse::Slide,
+            energy_max: 1.0,
+            food_energy: 0.5,
+            metabolism_cost: 0.0001,
+            speed_cost: 0.05,
+            acceleration_cost: 0.01,
+            turn_cost: 0.0002,
+            starvation: Starvation::Ignore,
+            energy_fitness_weight: 0.0,
+            lifespan: 5000,
+            foods_per_offspring: 3,
+            offspring_energy: 0.5,
+            offspring_distance: 0.02,
+            population_bounds: [10, 100],
+            collision_radius: 0.01,
+            evolve_body: false,
+            radius_bounds: [0.005, 0.02],
+            speed_max_bounds: [0.002, 0.01],
+            rotation_accel_bounds: [FRAC_PI_8, PI],
+            fov_range: FOV_RANGE,
+            fov_angle: FOV_ANGLE,
+            cells: CELLS,
+            predator_fov_range: FOV_RANGE,
+            predator_fov_angle: FOV_ANGLE,
+            channels: vec![Channel::Food],
+            evolve_eye: false,
+            fov_range_bounds: [0.05, 0.5],
+            fov_angle_bounds: [FRAC_PI_4, TAU],
+            vision_cost: 0.0,
+            predator_cells: CELLS,
+            predator_channels: vec![Channel::Prey],
+            behavior_grid: 4,
+            heatmap_grid: 0,
+            mutation_chance: 0.01,
+            mutation_coeff: 0.3,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod validate {
+        use super::*;
+        use test_case::test_case;
+
+        #[test]
+        fn test() {
+            assert_eq!(SimulationConfig::default().validate(), Ok(()));
+        }
+
+        #[test_case(
+            SimulationConfig { cells: 0, ..Default::default() },
+            "there must be at least one cell" ; "no cells"
+        )]
+        #[test_case(
+            SimulationConfig { predator_cells: 0, ..Default::default() },
+            "there must be at least one cell" ; "no predator cells"
+        )]
+        #[test_case(
+            SimulationConfig { fov_range: 0.0, ..Default::default() },
+            "fov_range must be positive" ; "zero fov range"
+        )]
+        #[test_case(
+            SimulationConfig { predator_fov_range: -0.1, ..Default::default() },
+            "fov_range must be positive" ; "negative predator fov range"
+        )]
+        #[test_case(
+            SimulationConfig { channels: vec![], ..Default::default() },
+            "there must be at least one channel" ; "no channels"
+        )]
+        #[test_case(
+            SimulationConfig { predator_channels: vec![], ..Default::default() },
+            "there must be at least one channel" ; "no predator channels"
+        )]
+        #[test_case(
+            SimulationConfig { channels: vec![Channel::Food, Channel::Food], ..Default::default() },
+            "duplicated channel: Food" ; "duplicated channel"
+        )]
+        #[test_case(
+            SimulationConfig {
+                obstacles: vec![Obstacle::Wall { from: na::Point2::new(0.5, 0.5), to: na::Point2::new(0.5, 0.5) }],
+                ..Default::default()
+            },
+            "wall must have a non-zero length" ; "invalid obstacle"
+        )]
+        #[test_case(
+            SimulationConfig {
+                obstacles: vec![
+                    Obstacle::Rect { min: na::Point2::new(0.0, 0.0), max: na::Point2::new(1.0, 0.6) },
+                    Obstacle::Circle { center: na::Point2::new(0.5, 0.8), radius: 0.8 },
+                ],
+                ..Default::default()
+            },
+            "obstacles cover the entire world" ; "no room left"
+        )]
+        #[test_case(
+            SimulationConfig {
+                food_spawners: vec![FoodSpawner::Seasons { period: 0, min_density: 0.5 }],
+                ..Default::default()
+            },
+            "season period must be positive" ; "invalid food spawner"
+        )]
+        #[test_case(
+            SimulationConfig {
+                food_spawners: vec![FoodSpawner::Patches { centers: vec![na::Point2::new(0.5, 0.5)], radius: 0.1 }],
+                obstacles: vec![Obstacle::Circle { center: na::Point2::new(0.5, 0.5), radius: 0.2 }],
+                ..Default::default()
+            },
+            "obstacles cover all food patches" ; "no room left in food patches"
+        )]
+        #[test_case(
+            SimulationConfig { generation_length: 0, ..Default::default() },
+            "generation_length must be positive" ; "zero generation length"
+        )]
+        #[test_case(
+            SimulationConfig { mutation_chance: 2.0, ..Default::default() },
+            "mutation_chance must be within <0, 1>" ; "mutation chance above one"
+        )]
+        #[test_case(
+            SimulationConfig { mutation_chance: -0.1, ..Default::default() },
+            "mutation_chance must be within <0, 1>" ; "negative mutation chance"
+        )]
+        #[test_case(
+            SimulationConfig { mutation_coeff: -0.3, ..Default::default() },
+            "negative mutation_coeff" ; "negative mutation coeff"
+        )]
+        fn invalid(config: SimulationConfig, expected: &str) {
+            assert_eq!(config.validate(), Err(expected.to_string()));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod from_toml {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = SimulationConfig::from_toml("animals = 100\nfov_range = 0.4\n").unwrap();
+
+            assert_eq!(config.animals, 100);
+            assert_eq!(config.fov_range, 0.4);
+            assert_eq!(config.foods, SimulationConfig::default().foods);
+        }
+
+        #[test]
+        fn roundtrip() {
+            let config = SimulationConfig::default();
+            assert_eq!(SimulationConfig::from_toml(&config.to_toml()).unwrap(), config);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod obstacles {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = SimulationConfig::from_toml(r#"
+                obstacle_response = "bounce"
+
+                [[obstacles]]
+                shape = "circle"
+                center = [0.5, 0.5]
+                radius = 0.1
+
+                [[obstacles]]
+                shape = "wall"
+                from = [0.1, 0.1]
+                to = [0.1, 0.9]
+            "#).unwrap();
+
+            assert_eq!(config.obstacle_response, ObstacleResponse::Bounce);
+            assert_eq!(config.obstacles, vec![
+                Obstacle::Circle { center: na::Point2::new(0.5, 0.5), radius: 0.1 },
+                Obstacle::Wall { from: na::Point2::new(0.1, 0.1), to: na::Point2::new(0.1, 0.9) },
+            ]);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod food_spawners {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = SimulationConfig::from_toml(r#"
+                [[food_spawners]]
+                kind = "regrowth"
+                delay = 100
+
+                [[food_spawners]]
+                kind = "patches"
+                centers = [[0.25, 0.25], [0.75, 0.75]]
+                radius = 0.1
+            "#).unwrap();
+
+            assert_eq!(config.food_spawners, vec![
+                FoodSpawner::Regrowth { delay: 100 },
+                FoodSpawner::Patches {
+                    centers: vec![na::Point2::new(0.25, 0.25), na::Point2::new(0.75, 0.75)],
+                    radius: 0.1,
+                },
+            ]);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod from_json {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = SimulationConfig::from_json(r#"{ "foods": 5, "cells": 3 }"#).unwrap();
+
+            assert_eq!(config.foods, 5);
+            assert_eq!(config.cells, 3);
+            assert_eq!(config.animals, SimulationConfig::default().animals);
+        }
+
+        #[test]
+        fn roundtrip() {
+            let config = SimulationConfig::default();
+            assert_eq!(SimulationConfig::from_json(&config.to_json()).unwrap(), config);
+        }
+
+        #[test]
+        fn rejects_garbage() {
+            assert!(SimulationConfig::from_json(r#"{ "foods": "many" }"#).is_err());
+        }
+    }
+}
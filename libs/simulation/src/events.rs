@@ -0,0 +1,480 @@
+use crate::*;
+
+/// Something that has happened in the world (see `Simulation::collect_events()`).
+///
+/// `time` is the number of the step the event has happened in, counting from zero across all
+/// generations.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum Event {
+    /// A bird has eaten the `food`-th food of the world
+    Ate {
+        time: usize,
+        animal: AnimalRef,
+        food: usize,
+        position: na::Point2<f32>,
+    },
+
+    /// A predator has caught a bird (which is then also reported as `Died`)
+    Caught {
+        time: usize,
+        predator: AnimalRef,
+        prey: AnimalRef,
+        position: na::Point2<f32>,
+    },
+
+    /// An animal has flown into the `obstacle`-th obstacle of the world
+    Collided {
+        time: usize,
+        animal: AnimalRef,
+        obstacle: usize,
+        position: na::Point2<f32>,
+    },
+
+    /// A bird has been born in the open-ended mode (see `Simulation::step_open_ended()`)
+    Born {
+        time: usize,
+        animal: AnimalRef,
+        parents: [AnimalRef; 2],
+    },
+
+    /// A random bird has joined the world in the open-ended mode, because there were too few
+    /// of them
+    Immigrated {
+        time: usize,
+        animal: AnimalRef,
+    },
+
+    Died {
+        time: usize,
+        animal: AnimalRef,
+        cause: Death,
+    },
+
+    /// The population has evolved, i.e. a generation has ended - all the animals referred to
+    /// by earlier events are gone
+    Evolved {
+        time: usize,
+    },
+}
+
+impl Event {
+    pub fn time(&self) -> usize {
+        return match *self {
+            Self::Ate { time, .. }
+            | Self::Caught { time, .. }
+            | Self::Collided { time, .. }
+            | Self::Born { time, .. }
+            | Self::Immigrated { time, .. }
+            | Self::Died { time, .. }
+            | Self::Evolved { time } => time,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Death {
+    /// Caught by a predator
+    Caught,
+
+    /// Ran out of energy (see `Starvation::Die`)
+    Starved,
+
+    /// Lived for `SimulationConfig::lifespan` steps (in the open-ended mode)
+    Age,
+}
+
+/// Animal an event is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnimalRef {
+    pub species: Species,
+
+    /// Index into `World::animals()` or, for predators, `World::predators()`, as of the event;
+    /// it changes when animals die or get born in the open-ended mode, so better use `id` to
+    /// follow a bird
+    pub index: usize,
+
+    /// Genealogy id of the bird; always `None` for predators, since only birds are tracked by
+    /// `Simulation::genealogy()` - predators can be told apart only by `index`, for as long as
+    /// their generation lasts
+    pub id: Option<ga::IndividualId>,
+}
+
+impl AnimalRef {
+    pub(crate) fn new(species: Species, index: usize, genealogy: &ga::Genealogy) -> Self {
+        let id = match species {
+            Species::Prey => genealogy.current().get(index).copied(),
+            Species::Predator => None,
+        };
+
+        return Self { species, index, id };
+    }
+}
+
+/// Called with each event right as it happens (see `Simulation::set_event_listener()`)
+pub type EventListener = Box<dyn FnMut(&Event) + Send>;
+
+/// Events of a simulation, delivered to the listener and/or collected until drained
+#[derive(Default)]
+pub(crate) struct Events {
+    collecting: bool,
+    collected: Vec<Event>,
+    listener: Option<EventListener>,
+}
+
+impl Events {
+    /// Whether there's anyone interested in events - if not, they aren't even created
+    pub(crate) fn is_enabled(&self) -> bool {
+        return self.collecting || self.listener.is_some();
+    }
+
+    pub(crate) fn emit(&mut self, event: impl FnOnce() -> Event) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let event = event();
+
+        if let Some(listener) = &mut self.listener {
+            listener(&event);
+        }
+
+        if self.collecting {
+            self.collected.push(event);
+        }
+    }
+}
+
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Starts (or, given `false`, stops) collecting events, so that they can be retrieved with
+    /// `drain_events()`; events aren't collected by default.
+    ///
+    /// Collecting events doesn't change how the simulation evolves.
+    pub fn collect_events(&mut self, collect: bool) {
+        self.events.collecting = collect;
+
+        if !collect {
+            self.events.collected.clear();
+        }
+    }
+
+    /// Returns events collected since the last call, oldest first
+    pub fn drain_events(&mut self) -> impl Iterator<Item=Event> + '_ {
+        return self.events.collected.drain(..);
+    }
+
+    /// Calls `listener` with each event, right as it happens (regardless of whether events are
+    /// being collected); `None` removes the listener
+    pub fn set_event_listener(&mut self, listener: Option<EventListener>) {
+        self.events.listener = listener;
+    }
+
+    /// Number of the current step, as reported by events
+    pub(crate) fn now(&self) -> usize {
+        // (`time` gets bumped right at the beginning of each step)
+        return self.time.saturating_sub(1);
+    }
+
+    /// Runs `f`, reporting animals that have starved while it ran
+    pub(crate) fn reporting_starvation(&mut self, f: impl FnOnce(&mut Self)) {
+        if !self.events.is_enabled() {
+            f(self);
+            return;
+        }
+
+        let alive: Vec<_> = self.world
+            .animals
+            .iter()
+            .chain(&self.world.predators)
+            .map(|animal| animal.alive)
+            .collect();
+
+        f(self);
+
+        let time = self.now();
+        let animals = self.world.animals.iter().enumerate();
+        let predators = self.world.predators.iter().enumerate();
+
+        for ((idx, animal), was_alive) in animals.chain(predators).zip(alive) {
+            if was_alive && !animal.alive {
+                self.events.emit(|| Event::Died {
+                    time,
+                    animal: AnimalRef::new(animal.species, idx, &self.genealogy),
+                    cause: Death::Starved,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn config() -> SimulationConfig {
+        return SimulationConfig {
+            generation_length: 200,
+            ..SimulationConfig::default()
+        };
+    }
+
+    fn simulation(config: SimulationConfig) -> (Simulation, ChaCha8Rng) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut sim = Simulation::new(config, &mut rng).unwrap();
+
+        sim.collect_events(true);
+
+        return (sim, rng);
+    }
+
+    fn count(events: &[Event], predicate: impl Fn(&Event) -> bool) -> usize {
+        return events.iter().filter(|event| predicate(event)).count();
+    }
+
+    mod collect_events {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let (mut sim, mut rng) = simulation(config());
+
+            for _ in 0..sim.config().generation_length {
+                assert!(sim.step(&mut rng, None).is_none());
+            }
+
+            let events: Vec<_> = sim.drain_events().collect();
+            let satiation: usize = sim.world().animals().iter().map(|animal| animal.satiation).sum();
+
+            assert!(satiation > 0);
+            assert_eq!(count(&events, |event| matches!(event, Event::Ate { .. })), satiation);
+            assert!(events.windows(2).all(|pair| pair[0].time() <= pair[1].time()));
+
+            for event in &events {
+                let Event::Ate { animal, position, .. } = event else {
+                    continue;
+                };
+
+                assert_eq!(animal.species, Species::Prey);
+                assert_eq!(animal.id, Some(sim.genealogy().current()[animal.index]));
+                assert!(sim.config().topology.distance(*position, sim.world().animals()[animal.index].position) < 1.0);
+            }
+
+            // (drained events are gone)
+            assert_eq!(sim.drain_events().count(), 0);
+
+            assert!(sim.step(&mut rng, None).is_some());
+            assert_eq!(
+                sim.drain_events().last(),
+                Some(Event::Evolved { time: sim.config().generation_length }),
+            );
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(config(), &mut rng).unwrap();
+
+            sim.train(&mut rng, None);
+
+            assert_eq!(sim.drain_events().count(), 0);
+        }
+
+        #[test]
+        fn doesnt_change_the_simulation() {
+            let (mut sim, mut rng) = simulation(config());
+
+            let mut other_rng = ChaCha8Rng::from_seed(Default::default());
+            let mut other = Simulation::new(config(), &mut other_rng).unwrap();
+
+            for _ in 0..2 {
+                let stats = sim.train(&mut rng, None);
+                let other_stats = other.train(&mut other_rng, None);
+
+                assert_eq!(stats.avg_fitness(), other_stats.avg_fitness());
+            }
+
+            let positions: Vec<_> = sim.world().animals().iter().map(Animal::position).collect();
+            let other_positions: Vec<_> = other.world().animals().iter().map(Animal::position).collect();
+
+            assert_eq!(positions, other_positions);
+        }
+    }
+
+    mod set_event_listener {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let (mut sim, mut rng) = simulation(config());
+            let heard = Arc::new(Mutex::new(Vec::new()));
+
+            sim.set_event_listener(Some(Box::new({
+                let heard = heard.clone();
+                move |event| heard.lock().unwrap().push(event.clone())
+            })));
+
+            sim.train(&mut rng, None);
+
+            let collected: Vec<_> = sim.drain_events().collect();
+
+            assert!(!collected.is_empty());
+            assert_eq!(*heard.lock().unwrap(), collected);
+        }
+    }
+
+    mod deaths {
+        use super::*;
+
+        #[test]
+        fn caught() {
+            let (mut sim, mut rng) = simulation(SimulationConfig {
+                predators: 5,
+                ..config()
+            });
+
+            for _ in 0..sim.config().generation_length {
+                sim.step(&mut rng, None);
+            }
+
+            let events: Vec<_> = sim.drain_events().collect();
+            let caught = sim.world().animals().iter().filter(|animal| animal.caught).count();
+
+            assert!(caught > 0);
+            assert_eq!(count(&events, |event| matches!(event, Event::Caught { .. })), caught);
+
+            assert_eq!(
+                count(&events, |event| matches!(event, Event::Died { cause: Death::Caught, .. })),
+                caught,
+            );
+
+            for event in &events {
+                if let Event::Caught { predator, prey, .. } = event {
+                    assert_eq!(predator.species, Species::Predator);
+                    assert_eq!(predator.id, None);
+                    assert!(sim.world().animals()[prey.index].caught);
+                }
+            }
+        }
+
+        #[test]
+        fn starved() {
+            let (mut sim, mut rng) = simulation(SimulationConfig {
+                starvation: Starvation::Die,
+                metabolism_cost: 0.01,
+                ..config()
+            });
+
+            for _ in 0..sim.config().generation_length {
+                sim.step(&mut rng, None);
+            }
+
+            let events: Vec<_> = sim.drain_events().collect();
+            let dead = sim.world().animals().iter().filter(|animal| !animal.alive).count();
+
+            assert!(dead > 0);
+
+            assert_eq!(
+                count(&events, |event| matches!(event, Event::Died { cause: Death::Starved, .. })),
+                dead,
+            );
+        }
+    }
+
+    mod collided {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let (mut sim, mut rng) = simulation(SimulationConfig {
+                obstacles: vec![Obstacle::Circle {
+                    center: na::Point2::new(0.5, 0.5),
+                    radius: 0.3,
+                }],
+                ..config()
+            });
+
+            sim.train(&mut rng, None);
+
+            let events: Vec<_> = sim.drain_events().collect();
+            let collisions: Vec<_> = events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Collided { obstacle, position, .. } => Some((*obstacle, *position)),
+                    _ => None,
+                })
+                .collect();
+
+            assert!(!collisions.is_empty());
+
+            for (obstacle, position) in collisions {
+                assert_eq!(obstacle, 0);
+                assert!(na::distance(&position, &na::Point2::new(0.5, 0.5)) > 0.3);
+            }
+        }
+    }
+
+    mod open_ended {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let (mut sim, mut rng) = simulation(SimulationConfig {
+                animals: 20,
+                lifespan: 300,
+                foods_per_offspring: 1,
+                population_bounds: [10, 40],
+                ..config()
+            });
+
+            let mut events = Vec::new();
+
+            for _ in 0..5 {
+                let stats = loop {
+                    if let Some(stats) = sim.step_open_ended(&mut rng, 200) {
+                        break stats;
+                    }
+                };
+
+                let before = events.len();
+                events.extend(sim.drain_events());
+                let events = &events[before..];
+
+                assert_eq!(count(events, |event| matches!(event, Event::Born { .. })), stats.born());
+                assert_eq!(count(events, |event| matches!(event, Event::Immigrated { .. })), stats.immigrated());
+
+                assert_eq!(
+                    count(events, |event| matches!(event, Event::Died { cause: Death::Age, .. })),
+                    stats.died_of_age(),
+                );
+            }
+
+            assert!(events.iter().any(|event| matches!(event, Event::Born { .. })));
+            assert!(events.iter().any(|event| matches!(event, Event::Died { cause: Death::Age, .. })));
+
+            for event in &events {
+                if let Event::Born { animal, parents, .. } = event {
+                    assert!(animal.id.is_some());
+                    assert!(parents.iter().all(|parent| parent.id.is_some() && parent.id != animal.id));
+                }
+            }
+        }
+    }
+}
@@ -6,44 +6,129 @@ use crate::*;
 /// - 0.1 = 10% of the map
 /// - 0.5 = 50% of the map
 /// - 1.0 = 100% of the map
-const FOV_RANGE: f32 = 0.25;
+pub(crate) const FOV_RANGE: f32 = 0.25;
 
 /// FOV_ANGLE determines the range of vision the bird has
-const FOV_ANGLE: f32 = PI * FRAC_PI_4;
+pub(crate) const FOV_ANGLE: f32 = PI * FRAC_PI_4;
 
 // CELLS ar the number of photoreceptors in a single eye
-const CELLS: usize = 9;
+pub(crate) const CELLS: usize = 9;
 
-#[derive(Debug)]
+/// Number of genes encoding an eye, see `Eye::as_genes()`
+pub(crate) const EYE_GENES: usize = 2;
+
+/// Kind of things a photoreceptor can be sensitive to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Channel {
+    Food,
+
+    /// Other animals of the same species as the one looking
+    Kin,
+
+    /// Birds
+    Prey,
+
+    Predators,
+
+    /// The closest point of each obstacle
+    Obstacles,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Eye {
     pub(crate) fov_range: f32,
     pub(crate) fov_angle: f32,
     pub(crate) cells: usize,
+    pub(crate) channels: Vec<Channel>,
 }
 
 impl Eye {
+    /// Creates an eye that sees only food (see `with_channels()`)
     pub fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
         assert!(fov_range > 0.0);
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
 
-        return Self { fov_range, fov_angle, cells };
+        return Self { fov_range, fov_angle, cells, channels: vec![Channel::Food] };
+    }
+
+    /// Makes each photoreceptor sensitive to `channels`, separately
+    pub fn with_channels(mut self, channels: Vec<Channel>) -> Self {
+        assert!(!channels.is_empty());
+
+        for (idx, channel) in channels.iter().enumerate() {
+            assert!(!channels[..idx].contains(channel), "duplicated channel: {channel:?}");
+        }
+
+        self.channels = channels;
+        return self;
+    }
+
+    pub fn fov_range(&self) -> f32 {
+        return self.fov_range;
+    }
+
+    pub fn fov_angle(&self) -> f32 {
+        return self.fov_angle;
     }
 
     pub fn cells(&self) -> usize {
         return self.cells;
     }
 
+    /// Area of the field of view - the larger, the more energy the eye consumes (see
+    /// `SimulationConfig::vision_cost`)
+    pub fn area(&self) -> f32 {
+        return self.fov_range * self.fov_range * self.fov_angle / 2.0;
+    }
+
+    /// Encodes `fov_range` and `fov_angle` as genes in <0, 1>, relative to the bounds set in
+    /// `config` (see `SimulationConfig::eye_from_genes()` for the inverse)
+    pub(crate) fn as_genes(&self, config: &SimulationConfig) -> [f32; EYE_GENES] {
+        return [
+            to_gene(self.fov_range, config.fov_range_bounds),
+            to_gene(self.fov_angle, config.fov_angle_bounds),
+        ];
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        return &self.channels;
+    }
+
+    /// Number of values returned by `process_vision()`: `cells × channels`
+    pub fn outputs(&self) -> usize {
+        return self.cells * self.channels.len();
+    }
+
+    /// `targets` are positions of things the eye might see, along with their channels; they can
+    /// be all such things in the world, or just the ones near `position` - targets farther than
+    /// `fov_range` are never seen anyway, and neither are targets on channels the eye isn't
+    /// sensitive to or targets hidden behind `obstacles`.
+    ///
+    /// Returns, for each photoreceptor, the intensity of every channel - i.e. `[cell 0 channel 0,
+    /// cell 0 channel 1, ..., cell 1 channel 0, ...]`.
     pub fn process_vision(
         &self,
+        topology: Topology,
+        obstacles: &[Obstacle],
         position: na::Point2<f32>,
         rotation: na::Rotation2<f32>,
-        foods: &[Food],
+        targets: impl IntoIterator<Item = (Channel, na::Point2<f32>)>,
     ) -> Vec<f32> {
-        let mut cells = vec![0.0; self.cells];
+        let mut outputs = vec![0.0; self.outputs()];
+
+        for (channel, target) in targets {
+            let Some(channel) = self.channels.iter().position(|&other| other == channel) else {
+                continue;
+            };
 
-        for food in foods {
-            let vec = food.position - position;
+            let vec = topology.displacement(position, target);
             let dist = vec.norm();
 
             if dist >= self.fov_range {
@@ -52,11 +137,17 @@ impl Eye {
                 continue;
             }
 
-            let angle = na::Rotation2::rotation_between(
-                &na::Vector2::x(),
-                &vec,
-            ).angle() // The vector direction relative to the X axis
-                - rotation.angle(); // AND include the bird's rotation
+            // (obstacles themselves are seen by their outlines, so they can't hide behind
+            // themselves)
+            let hidden = self.channels[channel] != Channel::Obstacles
+                && obstacles.iter().any(|obstacle| obstacle.blocks(position, position + vec));
+
+            if hidden {
+                continue;
+            }
+
+            let angle = trig::direction(&vec) // The vector direction relative to the X axis
+                - trig::angle(&rotation); // AND include the bird's rotation
 
             let angle = na::wrap(angle, -PI, PI); // And wrap around PI
 
@@ -87,14 +178,14 @@ impl Eye {
             // to the far right of the bird's vision), would get a `cell` value of the length of the
             // cells array - which is out of bounds.  Using `min` to force the "final" photoreceptor
             // to cover the 1.0 value.
-            let cell = (cell as usize).min(cells.len() - 1);
+            let cell = (cell as usize).min(self.cells - 1);
 
             // The "intensity" of the food in the bird's vision.
             let energy = (self.fov_range - dist) / self.fov_range;
-            cells[cell] += energy;
+            outputs[cell * self.channels.len() + channel] += energy;
         }
 
-        return cells;
+        return outputs;
     }
 }
 
@@ -106,7 +197,7 @@ impl Default for Eye {
 
 #[cfg(test)]
 mod tests {
-    use std::f32::consts::FRAC_PI_2;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
     use super::*;
 
@@ -123,13 +214,21 @@ mod tests {
     }
 
     impl TestCase {
+        /// Most of the test cases put foods right at the borders of the world, so - unless
+        /// stated otherwise - they take place in a world without wrapping
         fn run(self) {
+            self.run_in(Topology::Walls);
+        }
+
+        fn run_in(self, topology: Topology) {
             let eye = Eye::new(self.fov_range, self.fov_angle, TEST_EYE_CELLS);
 
             let actual_vision = eye.process_vision(
+                topology,
+                &[],
                 na::Point2::new(self.x, self.y),
                 na::Rotation2::new(self.rot),
-                &self.foods,
+                self.foods.iter().map(|food| (Channel::Food, food.position)),
             );
             let actual_vision: Vec<_> = actual_vision
                 .into_iter()
@@ -165,9 +264,7 @@ mod tests {
     }
 
     fn food(x: f32, y: f32) -> Food {
-        return Food {
-            position: na::Point2::new(x, y),
-        };
+        return Food::at(na::Point2::new(x, y));
     }
 
     mod different_fov_ranges {
@@ -465,4 +562,137 @@ mod tests {
             }.run()
         }
     }
+
+    mod across_borders {
+        use test_case::test_case;
+
+        use super::*;
+
+        /// World:
+        ///
+        /// ------------
+        /// |%       @>|
+        /// ------------
+        ///
+        /// On a torus the bird sees the food through the right edge, anywhere else the food is
+        /// way out of its range.
+        #[test_case(Topology::Torus, "      +      ")]
+        #[test_case(Topology::Walls, "             ")]
+        #[test_case(Topology::Bounce, "             ")]
+        fn right_edge(topology: Topology, expected_vision: &'static str) {
+            TestCase {
+                foods: vec![food(0.05, 0.5)],
+                fov_range: 0.25,
+                fov_angle: FRAC_PI_2,
+                x: 0.9,
+                y: 0.5,
+                rot: 0.0,
+                expected_vision,
+            }.run_in(topology)
+        }
+
+        /// Same, but the bird is looking up through the top edge, with the food slightly off
+        /// its axis
+        #[test_case(Topology::Torus, "       #     ")]
+        #[test_case(Topology::Walls, "             ")]
+        fn top_edge(topology: Topology, expected_vision: &'static str) {
+            TestCase {
+                foods: vec![food(0.04, 0.02)],
+                fov_range: 0.25,
+                fov_angle: FRAC_PI_2,
+                x: 0.05,
+                y: 0.95,
+                rot: FRAC_PI_2,
+                expected_vision,
+            }.run_in(topology)
+        }
+    }
+
+    mod channels {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let eye = Eye::new(1.0, FRAC_PI_2, 3).with_channels(vec![Channel::Food, Channel::Kin]);
+            assert_eq!(eye.outputs(), 6);
+
+            let vision = eye.process_vision(
+                Topology::Walls,
+                &[],
+                na::Point2::new(0.5, 0.5),
+                na::Rotation2::new(0.0),
+                [
+                    // Straight ahead
+                    (Channel::Food, na::Point2::new(1.0, 0.5)),
+                    // Ahead and to the left
+                    (Channel::Kin, na::Point2::new(1.0, 0.8)),
+                    // Eye isn't sensitive to predators
+                    (Channel::Predators, na::Point2::new(0.9, 0.5)),
+                ],
+            );
+
+            assert_eq!(vision.len(), 6);
+            assert_eq!(vision.iter().filter(|&&cell| cell > 0.0).count(), 2);
+            assert!(vision[2] > 0.0); // middle cell, food
+            assert!(vision[2 * 2 + 1] > 0.0); // left cell, kin
+        }
+
+        #[test]
+        fn obstacles() {
+            let eye = Eye::new(1.0, FRAC_PI_2, 3).with_channels(vec![Channel::Food, Channel::Obstacles]);
+            let wall = Obstacle::Wall { from: na::Point2::new(0.7, 0.0), to: na::Point2::new(0.7, 0.6) };
+
+            let vision = eye.process_vision(
+                Topology::Walls,
+                std::slice::from_ref(&wall),
+                na::Point2::new(0.5, 0.5),
+                na::Rotation2::new(0.0),
+                [
+                    // Hidden behind the wall
+                    (Channel::Food, na::Point2::new(0.9, 0.5)),
+                    (Channel::Obstacles, wall.closest_point(na::Point2::new(0.5, 0.5))),
+                ],
+            );
+
+            assert_eq!(vision, vec![0.0, 0.0, 0.0, 0.8, 0.0, 0.0]);
+        }
+
+        #[test]
+        #[should_panic(expected = "duplicated channel")]
+        fn duplicated() {
+            Eye::default().with_channels(vec![Channel::Food, Channel::Prey, Channel::Food]);
+        }
+    }
+
+    mod genes {
+        use super::*;
+        use approx::assert_relative_eq;
+
+        #[test]
+        fn roundtrip() {
+            let config = SimulationConfig::default();
+            let eye = config.eye_from_genes(Species::Prey, [0.25, 0.5]);
+
+            assert_relative_eq!(eye.fov_range(), 0.1625);
+            assert_relative_eq!(eye.fov_angle(), FRAC_PI_4 + 0.5 * (2.0 * PI - FRAC_PI_4));
+
+            let [range, angle] = eye.as_genes(&config);
+            assert_relative_eq!(range, 0.25);
+            assert_relative_eq!(angle, 0.5);
+        }
+
+        #[test]
+        fn clamps_mutated_genes() {
+            let config = SimulationConfig::default();
+            let eye = config.eye_from_genes(Species::Prey, [-0.3, 1.7]);
+
+            assert_relative_eq!(eye.fov_range(), config.fov_range_bounds[0]);
+            assert_relative_eq!(eye.fov_angle(), config.fov_angle_bounds[1]);
+        }
+
+        #[test]
+        fn area() {
+            assert_relative_eq!(Eye::new(0.5, PI, 3).area(), 0.125 * PI);
+        }
+    }
 }
\ No newline at end of file
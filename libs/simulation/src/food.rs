@@ -1,16 +1,32 @@
-use crate::{na, Rng, RngCore};
+use crate::na;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Food {
     pub(crate) position: na::Point2<f32>,
+    /// Multiplier of the energy a bird gets from eating this food
+    pub(crate) nutrition: f32,
+    /// Number of steps until the food grows back (see `FoodSpawner::Regrowth`)
+    pub(crate) regrow_in: usize,
+    /// Whether the food is out of season (see `FoodSpawner::Seasons`)
+    pub(crate) dormant: bool,
 }
 
 impl Food {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        return Self { position: rng.gen() };
+    pub(crate) fn at(position: na::Point2<f32>) -> Self {
+        return Self { position, nutrition: 1.0, regrow_in: 0, dormant: false };
     }
 
     pub fn position(&self) -> na::Point2<f32> {
         return self.position;
     }
+
+    pub fn nutrition(&self) -> f32 {
+        return self.nutrition;
+    }
+
+    /// Whether the food can be seen and eaten right now
+    pub fn is_available(&self) -> bool {
+        return self.regrow_in == 0 && !self.dormant;
+    }
 }
@@ -0,0 +1,352 @@
+use std::f32::consts::TAU;
+
+use rand::seq::SliceRandom;
+
+use crate::*;
+use crate::config::ensure;
+
+/// Strategy deciding where, when and how nutritious food appears.
+///
+/// Strategies of different kinds can be combined (see `SimulationConfig::food_spawners`); with
+/// none at all, eaten food instantly reappears at a random position.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum FoodSpawner {
+    /// Eaten food grows back only after `delay` steps
+    Regrowth {
+        delay: usize,
+    },
+
+    /// Food appears only within `radius` from one of `centers`
+    Patches {
+        centers: Vec<na::Point2<f32>>,
+        radius: f32,
+    },
+
+    /// Food wanders around, moving by `speed` in a random direction each step
+    Drift {
+        speed: f32,
+    },
+
+    /// Amount of available food goes from all of it down to `min_density` (a fraction) and back
+    /// every `period` steps
+    Seasons {
+        period: usize,
+        min_density: f32,
+    },
+
+    /// Each food is worth between `min` and `max` times `SimulationConfig::food_energy`
+    Nutrition {
+        min: f32,
+        max: f32,
+    },
+}
+
+impl FoodSpawner {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        return match *self {
+            Self::Regrowth { .. } => Ok(()),
+
+            Self::Patches { ref centers, radius } => {
+                ensure(!centers.is_empty(), "no food patches")?;
+                ensure(radius >= 0.0, "negative food patch radius")
+            }
+
+            Self::Drift { speed } => ensure(speed >= 0.0, "negative drift speed"),
+
+            Self::Seasons { period, min_density } => {
+                ensure(period > 0, "season period must be positive")?;
+                ensure((0.0..=1.0).contains(&min_density), "min_density must be within <0, 1>")
+            }
+
+            Self::Nutrition { min, max } => ensure(min >= 0.0 && min <= max, "invalid nutrition"),
+        };
+    }
+
+    /// Whether `config.obstacles` leave any room within the patches, judging by a grid of sample
+    /// points spread over each of them (other strategies don't decide where food appears)
+    pub(crate) fn has_room(&self, config: &SimulationConfig) -> bool {
+        const SAMPLES: usize = 20;
+
+        let Self::Patches { centers, radius } = self else {
+            return true;
+        };
+
+        let offsets: Vec<_> = (0..SAMPLES * SAMPLES)
+            .map(|idx| {
+                let x = (idx % SAMPLES) as f32 + 0.5;
+                let y = (idx / SAMPLES) as f32 + 0.5;
+
+                return na::Vector2::new(x, y) * (2.0 / SAMPLES as f32) - na::Vector2::new(1.0, 1.0);
+            })
+            .filter(|offset| offset.norm() <= 1.0)
+            .chain([na::Vector2::zeros()])
+            .collect();
+
+        return centers.iter().any(|center| {
+            return offsets.iter().any(|offset| {
+                let position = center + offset * *radius;
+                let (position, _) = config.topology.constrain(position, na::Rotation2::identity());
+
+                return !config.obstacles.iter().any(|obstacle| obstacle.contains(position));
+            });
+        });
+    }
+
+    /// Position of a new food, if this strategy decides about that
+    fn position(&self, rng: &mut dyn RngCore, config: &SimulationConfig) -> Option<na::Point2<f32>> {
+        let Self::Patches { centers, radius } = self else {
+            return None;
+        };
+
+        // Obstacles can't cover all the patches (see `has_room()`), so this eventually succeeds
+        loop {
+            let position = Self::position_in_patch(centers, *radius, rng, config);
+
+            if !config.obstacles.iter().any(|obstacle| obstacle.contains(position)) {
+                return Some(position);
+            }
+        }
+    }
+
+    fn position_in_patch(
+        centers: &[na::Point2<f32>],
+        radius: f32,
+        rng: &mut dyn RngCore,
+        config: &SimulationConfig,
+    ) -> na::Point2<f32> {
+        let center = centers.choose(rng).unwrap();
+
+        // (sqrt, so that food is spread evenly over the area of the patch)
+        let distance = radius * rng.gen::<f32>().sqrt();
+        let position = center + trig::random_rotation(rng) * na::Vector2::new(distance, 0.0);
+
+        return config.topology.constrain(position, na::Rotation2::identity()).0;
+    }
+
+    /// Nutritional value of a new food, if this strategy decides about that
+    fn nutrition(&self, rng: &mut dyn RngCore) -> Option<f32> {
+        let Self::Nutrition { min, max } = *self else {
+            return None;
+        };
+
+        return Some(if min < max { rng.gen_range(min..max) } else { min });
+    }
+
+    /// Moves, hides and shows `foods`, as needed by this strategy; `time` is the number of
+    /// steps simulated so far
+    fn process(&self, foods: &mut [Food], time: usize, rng: &mut dyn RngCore, config: &SimulationConfig) {
+        match *self {
+            Self::Drift { speed } => {
+                for food in foods {
+                    let position = food.position + trig::random_rotation(rng) * na::Vector2::new(speed, 0.0);
+                    let (position, _) = config.topology.constrain(position, na::Rotation2::identity());
+
+                    if !config.obstacles.iter().any(|obstacle| obstacle.contains(position)) {
+                        food.position = position;
+                    }
+                }
+            }
+
+            Self::Seasons { period, min_density } => {
+                let season = (time % period) as f32 / period as f32;
+                let density = min_density + (1.0 - min_density) * (1.0 + libm::cosf(TAU * season)) / 2.0;
+                let available = (density * foods.len() as f32).ceil() as usize;
+
+                for (idx, food) in foods.iter_mut().enumerate() {
+                    food.dormant = idx >= available;
+                }
+            }
+
+            _ => (),
+        }
+    }
+}
+
+/// Creates a new food, as decided by `spawners`
+pub(crate) fn spawn(rng: &mut dyn RngCore, config: &SimulationConfig) -> Food {
+    let spawners = &config.food_spawners;
+
+    let position = spawners
+        .iter()
+        .find_map(|spawner| spawner.position(rng, config))
+        .unwrap_or_else(|| world::random_position(rng, &config.obstacles));
+
+    let nutrition = spawners
+        .iter()
+        .find_map(|spawner| spawner.nutrition(rng))
+        .unwrap_or(1.0);
+
+    return Food { nutrition, ..Food::at(position) };
+}
+
+/// Replaces an eaten food with a new one, which - given `FoodSpawner::Regrowth` - isn't
+/// available right away
+pub(crate) fn respawn(food: &mut Food, rng: &mut dyn RngCore, config: &SimulationConfig) {
+    let dormant = food.dormant;
+
+    *food = spawn(rng, config);
+    food.dormant = dormant;
+
+    for spawner in &config.food_spawners {
+        if let FoodSpawner::Regrowth { delay } = *spawner {
+            food.regrow_in = delay;
+        }
+    }
+}
+
+/// Advances all the foods by one step
+pub(crate) fn process(foods: &mut [Food], time: usize, rng: &mut dyn RngCore, config: &SimulationConfig) {
+    for food in foods.iter_mut() {
+        food.regrow_in = food.regrow_in.saturating_sub(1);
+    }
+
+    for spawner in &config.food_spawners {
+        spawner.process(foods, time, rng, config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn config(food_spawners: Vec<FoodSpawner>) -> SimulationConfig {
+        return SimulationConfig { food_spawners, ..SimulationConfig::default() };
+    }
+
+    mod spawn {
+        use super::*;
+
+        #[test]
+        fn uniform() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let food = spawn(&mut rng, &config(vec![]));
+
+            assert!(food.is_available());
+            assert_eq!(food.nutrition(), 1.0);
+        }
+
+        #[test]
+        fn patches() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let centers = vec![na::Point2::new(0.2, 0.2), na::Point2::new(0.7, 0.5)];
+            let config = config(vec![FoodSpawner::Patches { centers: centers.clone(), radius: 0.1 }]);
+
+            for _ in 0..100 {
+                let food = spawn(&mut rng, &config);
+                assert!(centers.iter().any(|center| na::distance(center, &food.position()) <= 0.1));
+            }
+        }
+
+        #[test]
+        fn nutrition() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = config(vec![FoodSpawner::Nutrition { min: 0.5, max: 2.0 }]);
+
+            let nutritions: Vec<_> = (0..100).map(|_| spawn(&mut rng, &config).nutrition()).collect();
+
+            assert!(nutritions.iter().all(|nutrition| (0.5..2.0).contains(nutrition)));
+            assert!(nutritions.iter().any(|&nutrition| nutrition != nutritions[0]));
+        }
+    }
+
+    mod has_room {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let patches = |radius| {
+                return FoodSpawner::Patches { centers: vec![na::Point2::new(0.5, 0.5)], radius };
+            };
+
+            let config = |radius| {
+                return SimulationConfig {
+                    obstacles: vec![Obstacle::Circle { center: na::Point2::new(0.5, 0.5), radius }],
+                    ..SimulationConfig::default()
+                };
+            };
+
+            assert!(patches(0.2).has_room(&config(0.1)));
+            assert!(!patches(0.1).has_room(&config(0.2)));
+            assert!(!patches(0.0).has_room(&config(0.1)));
+            assert!(FoodSpawner::Regrowth { delay: 3 }.has_room(&config(0.1)));
+        }
+
+        #[test]
+        fn spawn_around_obstacles() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                food_spawners: vec![FoodSpawner::Patches { centers: vec![na::Point2::new(0.5, 0.5)], radius: 0.2 }],
+                obstacles: vec![Obstacle::Circle { center: na::Point2::new(0.5, 0.5), radius: 0.19 }],
+                ..SimulationConfig::default()
+            };
+
+            for _ in 0..100 {
+                let food = spawn(&mut rng, &config);
+                assert!(!config.obstacles[0].contains(food.position()));
+            }
+        }
+    }
+
+    mod respawn {
+        use super::*;
+
+        #[test]
+        fn regrowth() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = config(vec![FoodSpawner::Regrowth { delay: 3 }]);
+            let mut foods = vec![spawn(&mut rng, &config)];
+
+            respawn(&mut foods[0], &mut rng, &config);
+
+            for _ in 0..3 {
+                assert!(!foods[0].is_available());
+                process(&mut foods, 0, &mut rng, &config);
+            }
+
+            assert!(foods[0].is_available());
+        }
+    }
+
+    mod process {
+        use super::*;
+
+        #[test]
+        fn drift() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = config(vec![FoodSpawner::Drift { speed: 0.01 }]);
+            let mut foods = vec![Food::at(na::Point2::new(0.5, 0.5))];
+
+            process(&mut foods, 0, &mut rng, &config);
+
+            assert_relative_eq!(na::distance(&foods[0].position(), &na::Point2::new(0.5, 0.5)), 0.01, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn seasons() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = config(vec![FoodSpawner::Seasons { period: 100, min_density: 0.2 }]);
+            let mut foods: Vec<_> = (0..10).map(|_| spawn(&mut rng, &config)).collect();
+
+            let mut available_at = |time| {
+                process(&mut foods, time, &mut rng, &config);
+                return foods.iter().filter(|food| food.is_available()).count();
+            };
+
+            assert_eq!(available_at(0), 10);
+            assert_eq!(available_at(25), 6);
+            assert_eq!(available_at(50), 2);
+            assert_eq!(available_at(100), 10);
+        }
+    }
+}
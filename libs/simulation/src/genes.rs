@@ -0,0 +1,32 @@
+//! Helpers for encoding bounded traits (e.g. `Eye::fov_range`) as genes in <0, 1>
+
+pub(crate) fn to_gene(value: f32, [min, max]: [f32; 2]) -> f32 {
+    if max <= min {
+        return 0.0;
+    }
+
+    return ((value - min) / (max - min)).clamp(0.0, 1.0);
+}
+
+/// Decodes a gene; genes can be mutated out of <0, 1>, in which case they're clamped back
+pub(crate) fn from_gene(gene: f32, [min, max]: [f32; 2]) -> f32 {
+    return min + (max - min) * gene.clamp(0.0, 1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use test_case::test_case;
+
+    #[test_case(0.5, [0.0, 1.0], 0.5)]
+    #[test_case(3.0, [2.0, 6.0], 0.25)]
+    #[test_case(1.0, [2.0, 6.0], 0.0)]
+    #[test_case(9.0, [2.0, 6.0], 1.0)]
+    #[test_case(4.0, [4.0, 4.0], 0.0)]
+    fn roundtrip(value: f32, bounds: [f32; 2], expected_gene: f32) {
+        let gene = to_gene(value, bounds);
+        assert_relative_eq!(gene, expected_gene);
+        assert_relative_eq!(from_gene(gene, bounds), value.clamp(bounds[0], bounds[1]));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::*;
+
+/// Grid of `size`×`size` counters laid over the world, e.g. of how many times birds have been
+/// seen in each cell (see `Statistics::occupancy()`)
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heatmap {
+    size: usize,
+    values: Vec<f32>,
+}
+
+impl Heatmap {
+    pub fn new(size: usize) -> Self {
+        return Self {
+            size,
+            values: vec![0.0; size * size],
+        };
+    }
+
+    /// Number of cells per side
+    pub fn size(&self) -> usize {
+        return self.size;
+    }
+
+    /// Counters of all the cells, row by row - starting with the row at `y = 0`
+    pub fn values(&self) -> &[f32] {
+        return &self.values;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> f32 {
+        return self.values[y * self.size + x];
+    }
+
+    /// Value of the hottest cell (zero for an empty heatmap)
+    pub fn max(&self) -> f32 {
+        return self.values.iter().copied().fold(0.0, f32::max);
+    }
+
+    pub fn sum(&self) -> f32 {
+        return self.values.iter().sum();
+    }
+
+    /// Bumps the counter of the cell `position` lies in
+    pub(crate) fn add(&mut self, position: na::Point2<f32>) {
+        let cell = |coord: f32| ((coord * self.size as f32) as usize).min(self.size - 1);
+        self.values[cell(position.y) * self.size + cell(position.x)] += 1.0;
+    }
+}
+
+/// Heatmaps of the current generation, present only if `SimulationConfig::heatmap_grid` is
+/// enabled
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Heatmaps {
+    pub(crate) occupancy: Heatmap,
+    pub(crate) feeding: Heatmap,
+}
+
+impl Heatmaps {
+    pub(crate) fn new(config: &SimulationConfig) -> Option<Self> {
+        if config.heatmap_grid == 0 {
+            return None;
+        }
+
+        return Some(Self {
+            occupancy: Heatmap::new(config.heatmap_grid),
+            feeding: Heatmap::new(config.heatmap_grid),
+        });
+    }
+}
+
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Where birds have been during the current generation so far - each step, every living
+    /// bird adds one to the cell it's in; `None` unless `SimulationConfig::heatmap_grid` is set
+    pub fn occupancy(&self) -> Option<&Heatmap> {
+        return self.heatmaps.as_ref().map(|heatmaps| &heatmaps.occupancy);
+    }
+
+    /// Where birds have eaten during the current generation so far - each food eaten adds one
+    /// to the cell it's been in; `None` unless `SimulationConfig::heatmap_grid` is set
+    pub fn feeding(&self) -> Option<&Heatmap> {
+        return self.heatmaps.as_ref().map(|heatmaps| &heatmaps.feeding);
+    }
+
+    pub(crate) fn process_occupancy(&mut self) {
+        let Some(heatmaps) = &mut self.heatmaps else {
+            return;
+        };
+
+        for animal in self.world.animals.iter().filter(|animal| animal.alive) {
+            heatmaps.occupancy.add(animal.position);
+        }
+    }
+
+    /// Returns heatmaps of the generation that's just ended, starting new ones
+    pub(crate) fn take_heatmaps(&mut self) -> Option<Heatmaps> {
+        let heatmaps = Heatmaps::new(&self.config)?;
+        return self.heatmaps.replace(heatmaps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    mod add {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut heatmap = Heatmap::new(4);
+
+            heatmap.add(na::Point2::new(0.0, 0.0));
+            heatmap.add(na::Point2::new(0.1, 0.2));
+            heatmap.add(na::Point2::new(0.3, 0.9));
+            heatmap.add(na::Point2::new(1.0, 1.0));
+
+            assert_eq!(heatmap.get(0, 0), 2.0);
+            assert_eq!(heatmap.get(1, 3), 1.0);
+            assert_eq!(heatmap.get(3, 3), 1.0);
+            assert_eq!(heatmap.values()[3 * 4 + 1], 1.0);
+            assert_eq!(heatmap.max(), 2.0);
+            assert_eq!(heatmap.sum(), 4.0);
+        }
+    }
+
+    mod simulation {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            return SimulationConfig {
+                generation_length: 200,
+                heatmap_grid: 10,
+                ..SimulationConfig::default()
+            };
+        }
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(config(), &mut rng).unwrap();
+
+            for _ in 0..200 {
+                sim.step(&mut rng, None);
+            }
+
+            let satiation: usize = sim.world().animals().iter().map(|animal| animal.satiation).sum();
+            let occupancy = sim.occupancy().unwrap().clone();
+            let feeding = sim.feeding().unwrap().clone();
+
+            assert_eq!(occupancy.size(), 10);
+            assert_eq!(occupancy.sum(), (200 * sim.world().animals().len()) as f32);
+            assert_eq!(feeding.sum(), satiation as f32);
+
+            let stats = sim.step(&mut rng, None).unwrap();
+
+            // (the last step counts too)
+            assert!(stats.occupancy().unwrap().sum() > occupancy.sum());
+            assert!(stats.feeding().unwrap().sum() >= feeding.sum());
+
+            // (and the next generation starts from scratch)
+            assert_eq!(sim.occupancy().unwrap().sum(), 0.0);
+            assert_eq!(sim.feeding().unwrap().sum(), 0.0);
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+
+            let stats = sim.train(&mut rng, Some(10));
+
+            assert!(sim.occupancy().is_none());
+            assert!(stats.occupancy().is_none());
+            assert!(stats.feeding().is_none());
+        }
+
+        #[test]
+        fn open_ended() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(config(), &mut rng).unwrap();
+
+            let stats = loop {
+                if let Some(stats) = sim.step_open_ended(&mut rng, 100) {
+                    break stats;
+                }
+            };
+
+            assert!(stats.occupancy().unwrap().sum() > 0.0);
+            assert_eq!(sim.occupancy().unwrap().sum(), 0.0);
+        }
+    }
+}
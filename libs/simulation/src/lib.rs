@@ -1,72 +1,289 @@
-use std::f32::consts::FRAC_PI_2;
-
 use nalgebra as na;
 use rand::{Rng, RngCore};
 
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 
-use self::{
-    animal_individual::*,
-    brain::*,
-};
+use self::{body::*, brain::*, events::Events, eye::*, genes::*, heatmap::Heatmaps, open_ended::*, spatial_index::*};
 pub use self::{
-    animal::Animal,
-    eye::Eye,
+    animal::{Animal, Species, Telemetry},
+    animal_individual::AnimalIndividual,
+    body::Body,
+    config::{SimulationConfig, Starvation},
+    events::{AnimalRef, Death, Event, EventListener},
+    eye::{Channel, Eye},
     food::Food,
+    food_spawner::FoodSpawner,
+    heatmap::Heatmap,
+    obstacle::{Obstacle, ObstacleResponse},
+    seeded::SeededSimulation,
+    statistics::Statistics,
+    topology::Topology,
     world::World,
 };
-pub use ga::Statistics;
+#[cfg(feature = "serde")]
+pub use self::{
+    replay::{Recorder, Replay},
+    snapshot::Snapshot,
+};
+pub use ga::{Genealogy, IndividualId, LineageRecord};
 
 mod animal;
 mod animal_individual;
+mod body;
 mod brain;
+mod config;
+mod events;
 mod eye;
 mod food;
+mod food_spawner;
+mod genes;
+mod heatmap;
+mod obstacle;
+mod open_ended;
+#[cfg(feature = "serde")]
+mod replay;
+mod seeded;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod spatial_index;
+mod statistics;
+mod topology;
+mod trig;
 mod world;
 
-// Non-zero speed prevents bird from being stuck in one place
-const SPEED_MIN: f32 = 0.001;
-// Prevent the bird from approaching the speed of causality `c`
-const SPEED_MAX: f32 = 0.005;
-// Prevent infinite accelerations in speed
-const SPEED_ACCEL: f32 = 0.2;
-// And in rotation
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-// Minimum number of steps before evolving the algorithm
-const GENERATION_LENGTH: usize = 2500;
+/// Optimiser used by `Simulation::new()`
+pub type DefaultOptimizer = ga::GeneticAlgorithm<ga::RouletteWheelSelection>;
 
-
-pub struct Simulation {
+pub struct Simulation<O = DefaultOptimizer> {
+    config: SimulationConfig,
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    /// Foods of `world`, rebuilt at the beginning of each step
+    food_index: SpatialIndex,
+    /// Birds of `world`, rebuilt at the beginning of each step
+    prey_index: SpatialIndex,
+    /// Predators of `world`, rebuilt at the beginning of each step
+    predator_index: SpatialIndex,
+    optimizer: O,
+    /// Predators evolve separately from birds, always using a plain genetic algorithm
+    predator_optimizer: DefaultOptimizer,
+    genealogy: ga::Genealogy,
+    novelty_search: Option<ga::NoveltySearch>,
+    behavior: fn(&Animal, &SimulationConfig) -> Vec<f32>,
+    /// Births and deaths since the last report of the open-ended mode
+    census: Census,
+    /// Number of steps simulated so far, across all generations
+    time: usize,
     age: usize,
+    events: Events,
+    /// Heatmaps of the current generation, if enabled
+    heatmaps: Option<Heatmaps>,
 }
 
 impl Simulation {
+    /// Fails if `config` is invalid
+    pub fn new(config: SimulationConfig, rng: &mut dyn RngCore) -> Result<Self, String> {
+        config.validate()?;
+
+        let ga = config.genetic_algorithm();
+        return Self::new_with_optimizer(config, rng, ga);
+    }
+
+    /// Same as `new()`, with the default config
     pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection::default(),
-            ga::UniformCrossover::default(),
-            ga::GaussianMutation::new(0.01, 0.3),
-        );
+        return Self::new(SimulationConfig::default(), rng).expect("default config is valid");
+    }
+}
 
-        return Self { world, ga, age: 0 };
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Creates a random world, evolved by the given optimiser, e.g.:
+    ///
+    /// - `ga::GeneticAlgorithm` with any selection method,
+    /// - `ga::MapElites`,
+    /// - `ga::AskTellOptimizer` wrapping an evolution strategy (see `cma_es()`),
+    /// - or your own `ga::Optimizer<AnimalIndividual>`.
+    ///
+    /// Fails if `config` is invalid.
+    pub fn new_with_optimizer(
+        config: SimulationConfig,
+        rng: &mut dyn RngCore,
+        optimizer: O,
+    ) -> Result<Self, String> {
+        config.validate()?;
+
+        let world = World::random(rng, &config);
+        let genealogy = ga::Genealogy::new(world.animals.len());
+
+        return Ok(Self::from_parts(config, world, genealogy, optimizer));
+    }
+
+    /// Assembles a simulation of `world`, which is yet to take its first step; `config` must
+    /// have been validated already
+    fn from_parts(config: SimulationConfig, world: World, genealogy: ga::Genealogy, optimizer: O) -> Self {
+        let food_index = SpatialIndex::for_items(world.foods.len());
+        let prey_index = SpatialIndex::for_items(world.animals.len());
+        let predator_index = SpatialIndex::for_items(world.predators.len());
+        let predator_optimizer = config.genetic_algorithm();
+        let heatmaps = Heatmaps::new(&config);
+
+        return Self {
+            config,
+            world,
+            food_index,
+            prey_index,
+            predator_index,
+            optimizer,
+            predator_optimizer,
+            genealogy,
+            novelty_search: None,
+            behavior: Animal::behavior,
+            census: Census::default(),
+            time: 0,
+            age: 0,
+            events: Events::default(),
+            heatmaps,
+        };
+    }
+
+    /// Same as `new_with_optimizer()`, with the default config
+    pub fn random_with_optimizer(rng: &mut dyn RngCore, optimizer: O) -> Self {
+        return Self::new_with_optimizer(SimulationConfig::default(), rng, optimizer)
+            .expect("default config is valid");
+    }
+
+    /// Keeps the current world, genealogy and settings, but evolves it using `optimizer` from
+    /// now on
+    pub fn with_optimizer<P>(self, optimizer: P) -> Simulation<P>
+        where
+            P: ga::Optimizer<AnimalIndividual>
+    {
+        return Simulation {
+            config: self.config,
+            world: self.world,
+            food_index: self.food_index,
+            prey_index: self.prey_index,
+            predator_index: self.predator_index,
+            optimizer,
+            predator_optimizer: self.predator_optimizer,
+            genealogy: self.genealogy,
+            novelty_search: self.novelty_search,
+            behavior: self.behavior,
+            census: self.census,
+            time: self.time,
+            age: self.age,
+            events: self.events,
+            heatmaps: self.heatmaps,
+        };
+    }
+
+    pub fn config(&self) -> &SimulationConfig {
+        return &self.config;
     }
 
     pub fn world(&self) -> &World {
         return &self.world;
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore, generation_length: Option<usize>) -> Option<ga::Statistics> {
-        self.process_collisions(rng);
-        self.process_brains();
-        self.process_movements();
+    pub fn optimizer(&self) -> &O {
+        return &self.optimizer;
+    }
+
+    pub fn optimizer_mut(&mut self) -> &mut O {
+        return &mut self.optimizer;
+    }
+
+    /// Sets the function describing how a bird behaved, which is what novelty search and
+    /// `ga::MapElites` look at; defaults to `Animal::behavior()`.
+    pub fn set_behavior_descriptor(&mut self, behavior: fn(&Animal, &SimulationConfig) -> Vec<f32>) {
+        self.behavior = behavior;
+    }
+
+    /// Enables (or, given `None`, disables) novelty search: birds are then selected for
+    /// reproduction based on how differently they behave (see `set_behavior_descriptor()`),
+    /// instead of (or in addition to) how much they've eaten.
+    ///
+    /// Statistics keep on reporting the number of foods eaten.
+    pub fn set_novelty_search(&mut self, novelty_search: Option<ga::NoveltySearch>) {
+        self.novelty_search = novelty_search;
+    }
+
+    pub fn novelty_search(&self) -> Option<&ga::NoveltySearch> {
+        return self.novelty_search.as_ref();
+    }
+
+    /// CMA-ES preset: the initial distribution is centered at the average brain of the current
+    /// birds and the strategy asks for as many candidates as there are birds
+    pub fn cma_es(&self, sigma: f32) -> ga::AskTellOptimizer<ga::CmaEs> {
+        let animals = &self.world.animals;
+        let mut mean: Vec<f32> = animals[0].as_chromosome(&self.config).into_iter().collect();
+
+        for animal in &animals[1..] {
+            for (sum, gene) in mean.iter_mut().zip(animal.as_chromosome(&self.config)) {
+                *sum += gene;
+            }
+        }
+
+        let mean = mean
+            .into_iter()
+            .map(|sum| sum / (animals.len() as f32))
+            .collect();
+
+        return ga::AskTellOptimizer::new(ga::CmaEs::new(mean, sigma, animals.len()));
+    }
+
+    /// (μ,λ) / (μ+λ) evolution strategy preset: the first `mu` current birds become the initial
+    /// parents and the strategy breeds as many offspring as there are birds
+    pub fn mu_lambda_es(
+        &self,
+        mu: usize,
+        sigma: f32,
+        selection: ga::EsSelection,
+    ) -> ga::AskTellOptimizer<ga::MuLambdaEs> {
+        let parents = self.world
+            .animals
+            .iter()
+            .take(mu)
+            .map(|animal| animal.as_chromosome(&self.config))
+            .collect();
+
+        let es = ga::MuLambdaEs::new(parents, self.world.animals.len(), sigma, selection);
+        return ga::AskTellOptimizer::new(es);
+    }
+
+    /// Family tree of every bird that has ever lived in this simulation (predators aren't
+    /// tracked)
+    pub fn genealogy(&self) -> &ga::Genealogy {
+        return &self.genealogy;
+    }
+
+    /// Returns the genealogy id of `animal`, or `None` if it doesn't live in this simulation's
+    /// world or if it's a predator.
+    pub fn id_of(&self, animal: &Animal) -> Option<ga::IndividualId> {
+        // `world.animals` is always kept in the same order as `genealogy.current()`
+        return self.world
+            .animals
+            .iter()
+            .position(|other| std::ptr::eq(other, animal))
+            .map(|idx| self.genealogy.current()[idx]);
+    }
+
+    /// Returns the lineage record of `animal` followed by records of all of its ancestors,
+    /// youngest first (see `ga::Genealogy::ancestry()`).
+    pub fn ancestry(&self, animal: &Animal) -> Option<Vec<&ga::LineageRecord>> {
+        return self
+            .id_of(animal)
+            .map(|id| self.genealogy.ancestry(id));
+    }
+
+    pub fn step(&mut self, rng: &mut dyn RngCore, generation_length: Option<usize>) -> Option<Statistics> {
+        self.process_world(rng);
 
         let generation_length = match generation_length {
             Some(gl) => gl,
-            None => GENERATION_LENGTH
+            None => self.config.generation_length
         };
 
         self.age += 1;
@@ -77,7 +294,7 @@ impl Simulation {
     }
 
     /// Fast-forwards until the end of the current generation.
-    pub fn train(&mut self, rng: &mut dyn RngCore, generation_length: Option<usize>) -> ga::Statistics {
+    pub fn train(&mut self, rng: &mut dyn RngCore, generation_length: Option<usize>) -> Statistics {
         loop {
             if let Some(summary) = self.step(rng, generation_length) {
                 return summary;
@@ -85,70 +302,1166 @@ impl Simulation {
         }
     }
 
+    /// Simulates a single step of the world itself, the same in every mode
+    fn process_world(&mut self, rng: &mut dyn RngCore) {
+        self.process_foods(rng);
+        self.process_collisions(rng);
+
+        self.reporting_starvation(|sim| {
+            sim.process_brains();
+            sim.process_movements();
+        });
+
+        self.process_occupancy();
+    }
+
+    fn process_foods(&mut self, rng: &mut dyn RngCore) {
+        food_spawner::process(&mut self.world.foods, self.time, rng, &self.config);
+        self.time += 1;
+    }
+
     fn process_collisions(&mut self, rng: &mut dyn RngCore) {
+        let time = self.now();
+
+        // Foods might've been moved since the last step (e.g. by `evolve()`)
+        self.food_index.rebuild(self.world.foods.iter().map(Food::position));
+
         // To reduce complexity, assuming birds are circulate, and just checking if radii between
         // bird and food is <= the sum of their radii.
-        // If a collision is found, move the food to a random location ("eaten" and "spawned" a new one)
-        for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
-                let distance = na::distance(&animal.position, &food.position);
-
-                if distance <= 0.01 {
-                    animal.satiation += 1;
-                    food.position = rng.gen();
+        // If a collision is found, replace the food with a new one, as decided by
+        // `config.food_spawners` ("eaten" and "spawned" a new one)
+        for (animal_idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if !animal.alive {
+                continue;
+            }
+
+            for idx in self.food_index.query(animal.position, animal.body.radius) {
+                let food = &mut self.world.foods[idx];
+                let distance = self.config.topology.distance(animal.position, food.position);
+
+                if food.is_available() && distance <= animal.body.radius {
+                    self.events.emit(|| Event::Ate {
+                        time,
+                        animal: AnimalRef::new(Species::Prey, animal_idx, &self.genealogy),
+                        food: idx,
+                        position: food.position,
+                    });
+
+                    if let Some(heatmaps) = &mut self.heatmaps {
+                        heatmaps.feeding.add(food.position);
+                    }
+
+                    animal.eat(food.nutrition, &self.config);
+                    food_spawner::respawn(food, rng, &self.config);
+                    self.food_index.update(idx, food.position);
+                }
+            }
+        }
+
+        // Animals don't move until `process_movements()`, so these stay up to date for the
+        // entire step
+        self.prey_index.rebuild(self.world.animals.iter().map(Animal::position));
+        self.predator_index.rebuild(self.world.predators.iter().map(Animal::position));
+
+        // Predators catch birds the same way birds catch food, except that caught birds die
+        for (predator_idx, predator) in self.world.predators.iter_mut().enumerate() {
+            if !predator.alive {
+                continue;
+            }
+
+            for idx in self.prey_index.query(predator.position, predator.body.radius) {
+                let prey = &mut self.world.animals[idx];
+                let distance = self.config.topology.distance(predator.position, prey.position);
+
+                if prey.alive && distance <= predator.body.radius {
+                    prey.alive = false;
+                    prey.caught = true;
+                    predator.eat(1.0, &self.config);
+
+                    let prey = AnimalRef::new(Species::Prey, idx, &self.genealogy);
+
+                    self.events.emit(|| Event::Caught {
+                        time,
+                        predator: AnimalRef::new(Species::Predator, predator_idx, &self.genealogy),
+                        prey,
+                        position: predator.position,
+                    });
+
+                    self.events.emit(|| Event::Died { time, animal: prey, cause: Death::Caught });
                 }
             }
         }
     }
 
     fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision = animal.eye.process_vision(
-                animal.position,
-                animal.rotation,
-                &self.world.foods,
-            );
+        // Animals look around first and only then decide what to do, so that everyone sees the
+        // world in the same state
+        let visions: Vec<_> = self.world
+            .animals
+            .iter()
+            .enumerate()
+            .map(|(idx, animal)| self.process_vision(animal, idx))
+            .collect();
+
+        let predator_visions: Vec<_> = self.world
+            .predators
+            .iter()
+            .enumerate()
+            .map(|(idx, predator)| self.process_vision(predator, idx))
+            .collect();
+
+        let animals = self.world.animals.iter_mut().chain(&mut self.world.predators);
+
+        for (animal, vision) in animals.zip(visions.into_iter().chain(predator_visions)) {
+            if let Some(vision) = vision {
+                animal.process_brain(vision, &self.config);
+            }
+        }
+    }
+
+    /// Returns what `animal` - the `idx`-th animal of its species - sees, or `None` if it's
+    /// unable to act anyway (e.g. it's dead)
+    fn process_vision(&self, animal: &Animal, idx: usize) -> Option<Vec<f32>> {
+        if !animal.alive || animal.is_exhausted(&self.config) {
+            return None;
+        }
+
+        let position = animal.position;
+        let range = animal.eye.fov_range;
+        let mut targets = Vec::new();
 
-            let response = animal.brain.nn.propagate(vision);
-            let speed_acceleration = response[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation_acceleration = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+        for &channel in animal.eye.channels() {
+            let positions = match channel {
+                Channel::Food => self.food_index
+                    .query(position, range)
+                    .into_iter()
+                    .map(|idx| &self.world.foods[idx])
+                    .filter(|food| food.is_available())
+                    .map(Food::position)
+                    .collect(),
 
-            animal.speed = (animal.speed + speed_acceleration).clamp(SPEED_MIN, SPEED_MAX);
-            animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation_acceleration);
-            // rotation is already wrapped from [0, 2π]
+                Channel::Kin => {
+                    self.nearby_animals(animal.species, position, range, Some(idx))
+                }
+
+                Channel::Prey => {
+                    let except = (animal.species == Species::Prey).then_some(idx);
+                    self.nearby_animals(Species::Prey, position, range, except)
+                }
+
+                Channel::Predators => {
+                    let except = (animal.species == Species::Predator).then_some(idx);
+                    self.nearby_animals(Species::Predator, position, range, except)
+                }
+
+                Channel::Obstacles => self.world
+                    .obstacles
+                    .iter()
+                    .map(|obstacle| obstacle.closest_point(position))
+                    .collect(),
+            };
+
+            targets.extend(positions.into_iter().map(|position| (channel, position)));
         }
+
+        return Some(animal.eye.process_vision(
+            self.config.topology,
+            &self.world.obstacles,
+            position,
+            animal.rotation,
+            targets,
+        ));
+    }
+
+    /// Positions of living animals of given species that are (roughly) within `range` from
+    /// `position`, except for the `except`-th one
+    fn nearby_animals(
+        &self,
+        species: Species,
+        position: na::Point2<f32>,
+        range: f32,
+        except: Option<usize>,
+    ) -> Vec<na::Point2<f32>> {
+        let (animals, index) = match species {
+            Species::Prey => (&self.world.animals, &self.prey_index),
+            Species::Predator => (&self.world.predators, &self.predator_index),
+        };
+
+        return index
+            .query(position, range)
+            .into_iter()
+            .filter(|&idx| Some(idx) != except)
+            .map(|idx| &animals[idx])
+            .filter(|animal| animal.alive)
+            .map(|animal| animal.position)
+            .collect();
     }
 
     fn process_movements(&mut self) {
-        for animal in &mut self.world.animals {
-            animal.position += animal.rotation * na::Vector2::new(animal.speed, 0.0);
-            animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+        let time = self.now();
+        let animals = self.world.animals.iter_mut().enumerate();
+        let predators = self.world.predators.iter_mut().enumerate();
+
+        for (idx, animal) in animals.chain(predators) {
+            if let Some(obstacle) = animal.process_movement(&self.world.obstacles, &self.config) {
+                self.events.emit(|| Event::Collided {
+                    time,
+                    animal: AnimalRef::new(animal.species, idx, &self.genealogy),
+                    obstacle,
+                    position: animal.position,
+                });
+            }
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+    fn evolve(&mut self, rng: &mut dyn RngCore) -> Statistics {
         self.age = 0;
 
         // 1. Prepare the current population of birds to go into the GA (must have `Individual` trait
-        let current_population: Vec<_> = self.world
+        let mut current_population: Vec<_> = self.world
             .animals
             .iter()
-            .map(AnimalIndividual::from_animal)
+            .map(|animal| AnimalIndividual::from_animal(animal, &self.config))
             .collect();
 
+        let mut stats = self.evaluate(&mut current_population);
+
         // 2. Evolve the population
-        let (evolved_population, stats) = self.ga.evolve(rng, &current_population);
+        let (evolved_population, _) = self.optimizer.evolve(
+            rng,
+            &current_population,
+            &mut self.genealogy,
+        );
 
         // 3. Return from the GA
         self.world.animals = evolved_population
             .into_iter()
-            .map(|individual| individual.into_animal(rng))
+            .map(|individual| individual.into_animal(rng, &self.config))
             .collect();
 
+        // 3b. Predators co-evolve, as a separate population
+        if !self.world.predators.is_empty() {
+            stats.set_predators(self.evolve_predators(rng));
+        }
+
         // 4. Restart food (not required; just makes it easier to see when an evolution happens in the UI)
-        self.world.foods.iter_mut().for_each(|food| food.position = rng.gen());
+        for food in &mut self.world.foods {
+            *food = food_spawner::spawn(rng, &self.config);
+        }
+
+        let time = self.now();
+        self.events.emit(|| Event::Evolved { time });
+
+        return stats;
+    }
+
+    fn evolve_predators(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+        let current_population: Vec<_> = self.world
+            .predators
+            .iter()
+            .map(|predator| AnimalIndividual::from_animal(predator, &self.config))
+            .collect();
+
+        let (evolved_population, stats) = self.predator_optimizer.evolve(rng, &current_population);
+
+        self.world.predators = evolved_population
+            .into_iter()
+            .map(|individual| individual.into_animal_of(Species::Predator, rng, &self.config))
+            .collect();
+
+        return stats;
+    }
+
+    /// Computes statistics of the population, describes everyone's behavior and, if novelty
+    /// search is enabled, replaces each individual's fitness with the one assigned by novelty
+    /// search.
+    ///
+    /// `population` must be in the same order as `world.animals`.
+    fn evaluate(&mut self, population: &mut [AnimalIndividual]) -> Statistics {
+        let mut stats = Statistics::new(ga::Statistics::new(population), &self.world.animals, &self.config);
+        stats.set_heatmaps(self.take_heatmaps());
+
+        for (individual, animal) in population.iter_mut().zip(&self.world.animals) {
+            individual.set_behavior((self.behavior)(animal, &self.config));
+        }
+
+        if let Some(novelty_search) = &mut self.novelty_search {
+            let fitnesses = novelty_search.evaluate(population);
+
+            for (individual, fitness) in population.iter_mut().zip(fitnesses) {
+                individual.set_fitness(fitness);
+            }
+        }
+
+        return stats;
+    }
+}
+
+impl Simulation<ga::MapElites> {
+    /// MAP-Elites preset that sorts birds by their `Animal::flight_style()` - i.e. average speed
+    /// and average turn rate - into a `cells`×`cells` grid; fails if `config` is invalid
+    pub fn flight_styles(
+        config: SimulationConfig,
+        rng: &mut dyn RngCore,
+        cells: usize,
+    ) -> Result<Self, String> {
+        config.validate()?;
+
+        let map_elites = ga::MapElites::new(
+            vec![
+                ga::GridAxis::new(0.0, 1.0, cells),
+                ga::GridAxis::new(0.0, 1.0, cells),
+            ],
+            ga::UniformCrossover::new(),
+            config.mutation(),
+        );
+
+        let mut sim = Self::new_with_optimizer(config, rng, map_elites)?;
+        sim.set_behavior_descriptor(Animal::flight_style);
+        return Ok(sim);
+    }
+
+    /// Same as `flight_styles()`, with the default config
+    pub fn random_flight_styles(rng: &mut dyn RngCore, cells: usize) -> Self {
+        return Self::flight_styles(SimulationConfig::default(), rng, cells).expect("default config is valid");
+    }
+
+    /// Replaces the birds in the world with the MAP-Elites gallery - one bird per reached cell -
+    /// so that different flying styles can be watched side by side.
+    ///
+    /// Returns the number of spawned birds (zero if MAP-Elites hasn't reached any cell yet, in
+    /// which case the world is left untouched).
+    pub fn populate_from_elites(&mut self, rng: &mut dyn RngCore) -> usize {
+        let animals: Vec<_> = self.optimizer
+            .elites()
+            .map(|(_, elite)| {
+                return Animal::from_chromosome(Species::Prey, elite.chromosome().clone(), rng, &self.config);
+            })
+            .collect();
+
+        if animals.is_empty() {
+            return 0;
+        }
+
+        self.genealogy.record_founders(animals.len());
+        self.world.animals = animals;
+        self.age = 0;
+
+        return self.world.animals.len();
+    }
+}
+
+impl<S> Simulation<ga::GeneticAlgorithm<S>>
+    where
+        S: ga::SelectionMethod
+{
+    /// Steady-state counterpart of `step()`: instead of replacing the whole population at the
+    /// end of a generation, every `interval` steps `offspring` birds are born and the same
+    /// number of birds, chosen by `replacement_method`, die.
+    ///
+    /// Predators, if there are any, don't evolve in this mode.
+    pub fn step_steady_state<R>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        interval: usize,
+        offspring: usize,
+        replacement_method: &R,
+    ) -> Option<Statistics>
+        where
+            R: ga::ReplacementMethod
+    {
+        self.process_world(rng);
+
+        self.age += 1;
+        if self.age >= interval {
+            return Some(self.evolve_steady_state(rng, offspring, replacement_method));
+        }
+        return None;
+    }
+
+    fn evolve_steady_state<R>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        offspring: usize,
+        replacement_method: &R,
+    ) -> Statistics
+        where
+            R: ga::ReplacementMethod
+    {
+        self.age = 0;
+
+        // Birds are kept ordered from the oldest to the youngest, which is what the GA expects
+        let mut current_population: Vec<_> = self.world
+            .animals
+            .iter()
+            .map(|animal| AnimalIndividual::from_animal_by_rate(animal, &self.config))
+            .collect();
+
+        let stats = self.evaluate(&mut current_population);
+
+        let (replacements, _) = self.optimizer.evolve_steady_state_tracked(
+            rng,
+            &current_population,
+            offspring,
+            replacement_method,
+            &mut self.genealogy,
+        );
+
+        let replacements = replacements
+            .into_iter()
+            .map(|(idx, individual)| (idx, individual.into_animal(rng, &self.config)))
+            .collect();
+
+        ga::apply_replacements(&mut self.world.animals, replacements);
+
+        let time = self.now();
+        self.events.emit(|| Event::Evolved { time });
 
         return stats;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    mod step_steady_state {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+            let replacement = ga::ReplaceOldest::new();
+            let before = sim.genealogy().current().to_vec();
+
+            for _ in 0..9 {
+                assert!(sim.step_steady_state(&mut rng, 10, 4, &replacement).is_none());
+            }
+
+            assert!(sim.step_steady_state(&mut rng, 10, 4, &replacement).is_some());
+
+            let after = sim.genealogy().current().to_vec();
+
+            // The four oldest birds have been replaced by four newborns, appended at the end
+            assert_eq!(after.len(), 40);
+            assert_eq!(after[..36], before[4..]);
+            assert!(after[36..].iter().all(|id| !before.contains(id)));
+
+            let ages: Vec<_> = sim.world().animals().iter().map(|animal| animal.age()).collect();
+            assert!(ages[..36].iter().all(|&age| age == 10));
+            assert!(ages[36..].iter().all(|&age| age == 0));
+        }
+
+        #[test]
+        fn generation_free() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+            let replacement = ga::ReplaceWorst::new();
+
+            let evolutions = (0..100)
+                .filter_map(|_| sim.step_steady_state(&mut rng, 10, 4, &replacement))
+                .count();
+
+            assert_eq!(evolutions, 10);
+            assert_eq!(sim.world().animals().len(), 40);
+
+            // Survivors keep on living (and aging) instead of being reborn each generation
+            let oldest = sim.world().animals().iter().map(|animal| animal.age()).max().unwrap();
+            assert!(oldest > 10);
+
+            // (while the ones that have died don't come back)
+            assert_eq!(sim.genealogy().records().count(), 40 + 10 * 4);
+        }
+    }
+
+    mod ancestry {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+
+            for _ in 0..3 {
+                sim.train(&mut rng, Some(10));
+            }
+
+            let animal = &sim.world().animals()[0];
+            let ancestry = sim.ancestry(animal).unwrap();
+
+            assert_eq!(Some(ancestry[0].id), sim.id_of(animal));
+            assert_eq!(ancestry[0].generation, 3);
+            assert!(ancestry.iter().any(|record| record.parents.is_none()));
+
+            let stranger = Animal::random(&mut rng, sim.config());
+            assert!(sim.ancestry(&stranger).is_none());
+        }
+    }
+
+    mod novelty_search {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+
+            sim.set_novelty_search(Some(ga::NoveltySearch::new(
+                5,
+                0.0,
+                100,
+                ga::NoveltyFitness::Novelty,
+            )));
+
+            sim.train(&mut rng, Some(10));
+
+            let novelty_search = sim.novelty_search().unwrap();
+            assert_eq!(novelty_search.archive().len(), sim.world().animals().len());
+            assert!(novelty_search.archive().iter().all(|behavior| behavior.len() == 3));
+        }
+    }
+
+    mod optimizer {
+        use super::*;
+
+        /// Keeps the population as it is
+        struct Frozen;
+
+        impl ga::Optimizer<AnimalIndividual> for Frozen {
+            fn evolve(
+                &mut self,
+                _: &mut dyn RngCore,
+                population: &[AnimalIndividual],
+                _: &mut ga::Genealogy,
+            ) -> (Vec<AnimalIndividual>, ga::Statistics) {
+                let new_population = population
+                    .iter()
+                    .map(|individual| {
+                        let chromosome = ga::Individual::chromosome(individual).clone();
+                        return <AnimalIndividual as ga::Individual>::create(chromosome);
+                    })
+                    .collect();
+
+                return (new_population, ga::Statistics::new(population));
+            }
+        }
+
+        #[test]
+        fn custom() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random_with_optimizer(&mut rng, Frozen);
+
+            let brains = |sim: &Simulation<Frozen>| -> Vec<Vec<f32>> {
+                return sim.world()
+                    .animals()
+                    .iter()
+                    .map(|animal| animal.as_chromosome(sim.config()).into_iter().collect())
+                    .collect();
+            };
+
+            let before = brains(&sim);
+            sim.train(&mut rng, Some(10));
+            let after = brains(&sim);
+
+            assert_eq!(before, after);
+        }
+
+        #[test]
+        fn boxed() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = ga::GeneticAlgorithm::new(
+                ga::ScaledSelection::new(ga::RouletteWheelSelection::new(), ga::RankScaling::new()),
+                ga::UniformCrossover::new(),
+                ga::GaussianMutation::new(0.01, 0.3),
+            );
+            let optimizer: Box<dyn ga::Optimizer<AnimalIndividual>> = Box::new(ga);
+
+            let mut sim = Simulation::random_with_optimizer(&mut rng, optimizer);
+            sim.train(&mut rng, Some(10));
+
+            assert_eq!(sim.world().animals().len(), 40);
+        }
+    }
+
+    mod evolution_strategy {
+        use super::*;
+
+        #[test]
+        fn cma_es() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let sim = Simulation::random(&mut rng);
+
+            let cma_es = sim.cma_es(0.5);
+            let mut sim = sim.with_optimizer(cma_es);
+
+            for _ in 0..3 {
+                sim.train(&mut rng, Some(10));
+            }
+
+            assert_eq!(sim.world().animals().len(), 40);
+            assert_eq!(sim.genealogy().current().len(), 40);
+        }
+
+        #[test]
+        fn mu_lambda_es() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let sim = Simulation::random(&mut rng);
+
+            let es = sim.mu_lambda_es(10, 0.1, ga::EsSelection::Plus);
+            let mut sim = sim.with_optimizer(es);
+
+            for _ in 0..3 {
+                sim.train(&mut rng, Some(10));
+            }
+
+            assert_eq!(sim.world().animals().len(), 40);
+        }
+    }
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                animals: 7,
+                foods: 3,
+                cells: 5,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            assert_eq!(sim.world().animals().len(), 7);
+            assert_eq!(sim.world().foods().len(), 3);
+            assert_eq!(sim.world().animals()[0].eye.cells(), 5);
+
+            sim.train(&mut rng, Some(10));
+            assert_eq!(sim.world().animals().len(), 7);
+            assert_eq!(sim.world().animals()[0].eye.cells(), 5);
+        }
+
+        #[test]
+        fn invalid() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                speed_min: 0.01,
+                speed_max: 0.001,
+                ..SimulationConfig::default()
+            };
+
+            assert_eq!(Simulation::new(config, &mut rng).err().as_deref(), Some("invalid speed range"));
+        }
+
+        #[test]
+        fn invalid_mutation() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                mutation_chance: 2.0,
+                ..SimulationConfig::default()
+            };
+
+            let expected = Some("mutation_chance must be within <0, 1>");
+            assert_eq!(Simulation::new(config.clone(), &mut rng).err().as_deref(), expected);
+            assert_eq!(Simulation::flight_styles(config, &mut rng, 5).err().as_deref(), expected);
+        }
+    }
+
+    mod topology {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case(Topology::Torus)]
+        #[test_case(Topology::Walls)]
+        #[test_case(Topology::Bounce)]
+        fn birds_stay_in_the_world(topology: Topology) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                topology,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+
+            for _ in 0..200 {
+                sim.step(&mut rng, None);
+
+                for animal in sim.world().animals() {
+                    assert!((0.0..=1.0).contains(&animal.position().x));
+                    assert!((0.0..=1.0).contains(&animal.position().y));
+                }
+            }
+        }
+    }
+
+    mod energy {
+        use super::*;
+
+        fn starving(starvation: Starvation) -> SimulationConfig {
+            return SimulationConfig {
+                energy_max: 0.01,
+                metabolism_cost: 0.001,
+                starvation,
+                ..SimulationConfig::default()
+            };
+        }
+
+        #[test]
+        fn die() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(starving(Starvation::Die), &mut rng).unwrap();
+
+            for _ in 0..20 {
+                sim.step(&mut rng, None);
+            }
+
+            assert!(sim.world().animals().iter().all(|animal| !animal.is_alive()));
+
+            let positions: Vec<_> = sim.world().animals().iter().map(Animal::position).collect();
+            sim.step(&mut rng, None);
+            let positions_after: Vec<_> = sim.world().animals().iter().map(Animal::position).collect();
+            assert_eq!(positions, positions_after);
+
+            let stats = sim.train(&mut rng, Some(30));
+            assert_eq!(stats.starved(), 40);
+            assert_eq!(stats.avg_energy(), 0.0);
+
+            // A new generation is born alive
+            assert!(sim.world().animals().iter().all(Animal::is_alive));
+        }
+
+        #[test]
+        fn stop() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(starving(Starvation::Stop), &mut rng).unwrap();
+
+            for _ in 0..20 {
+                sim.step(&mut rng, None);
+            }
+
+            let exhausted: Vec<_> = sim.world()
+                .animals()
+                .iter()
+                .filter(|animal| animal.energy() <= 0.0)
+                .map(Animal::position)
+                .collect();
+
+            assert!(!exhausted.is_empty());
+            assert!(sim.world().animals().iter().all(Animal::is_alive));
+
+            sim.step(&mut rng, None);
+
+            for position in exhausted {
+                assert!(sim.world().animals().iter().any(|animal| animal.position() == position));
+            }
+        }
+
+        #[test]
+        fn eat() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = SimulationConfig::default();
+            let mut animal = Animal::random(&mut rng, &config);
+
+            animal.spend_energy(0.8, &config);
+            assert_relative_eq!(animal.energy(), 0.2);
+
+            animal.eat(1.0, &config);
+            assert_relative_eq!(animal.energy(), 0.7);
+
+            animal.eat(1.0, &config);
+            assert_relative_eq!(animal.energy(), config.energy_max);
+        }
+
+        #[test]
+        fn fitness() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                energy_fitness_weight: 2.0,
+                ..SimulationConfig::default()
+            };
+
+            let mut animal = Animal::random(&mut rng, &config);
+            animal.eat(1.0, &config);
+            animal.eat(1.0, &config);
+            animal.spend_energy(0.25, &config);
+
+            assert_relative_eq!(animal.fitness(&SimulationConfig::default()), 2.0);
+            assert_relative_eq!(animal.fitness(&config), 1.5);
+        }
+    }
+
+    mod predators {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                predators: 10,
+                collision_radius: 0.05,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            assert_eq!(sim.world().predators().len(), 10);
+            assert!(sim.world().predators().iter().all(|predator| predator.species() == Species::Predator));
+
+            let stats = sim.train(&mut rng, Some(200));
+
+            assert!(stats.caught() > 0);
+
+            // Every caught bird fed exactly one predator
+            let predators = stats.predators().unwrap();
+            assert_relative_eq!(predators.avg_fitness() * 10.0, stats.caught() as f32);
+
+            assert_eq!(sim.world().animals().len(), 40);
+            assert_eq!(sim.world().predators().len(), 10);
+            assert!(sim.world().predators().iter().all(|predator| predator.species() == Species::Predator));
+            assert!(sim.world().animals().iter().all(|animal| animal.species() == Species::Prey));
+        }
+
+        #[test]
+        fn caught_birds_lose_fitness() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = SimulationConfig::default();
+
+            let mut animal = Animal::random(&mut rng, &config);
+            animal.eat(1.0, &config);
+            animal.eat(1.0, &config);
+            animal.caught = true;
+
+            assert_relative_eq!(animal.fitness(&config), 2.0 - config.caught_penalty);
+        }
+
+        #[test]
+        fn none() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+
+            let stats = sim.train(&mut rng, Some(10));
+
+            assert!(sim.world().predators().is_empty());
+            assert!(stats.predators().is_none());
+            assert_eq!(stats.caught(), 0);
+        }
+    }
+
+    mod channels {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                predators: 5,
+                channels: vec![Channel::Food, Channel::Kin, Channel::Predators],
+                predator_channels: vec![Channel::Prey, Channel::Kin],
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            sim.train(&mut rng, Some(20));
+
+            let bird = &sim.world().animals()[0];
+            assert_eq!(bird.eye.outputs(), 27);
+            assert_eq!(bird.brain.nn.propagate(vec![0.0; 27]).len(), 2);
+
+            let predator = &sim.world().predators()[0];
+            assert_eq!(predator.eye.outputs(), 18);
+        }
+
+        #[test]
+        fn birds_see_each_other() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                animals: 2,
+                foods: 0,
+                channels: vec![Channel::Food, Channel::Kin],
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            sim.world.animals[0].position = na::Point2::new(0.5, 0.5);
+            sim.world.animals[0].rotation = na::Rotation2::new(0.0);
+            sim.world.animals[1].position = na::Point2::new(0.6, 0.5);
+            sim.prey_index.rebuild(sim.world.animals.iter().map(Animal::position));
+
+            let vision = sim.process_vision(&sim.world.animals[0], 0).unwrap();
+
+            // Middle photoreceptor sees kin, but not food
+            assert_eq!(vision[8], 0.0);
+            assert!(vision[9] > 0.0);
+            assert_eq!(vision.iter().filter(|&&cell| cell > 0.0).count(), 1);
+        }
+    }
+
+    mod obstacles {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case(ObstacleResponse::Slide)]
+        #[test_case(ObstacleResponse::Bounce)]
+        fn test(obstacle_response: ObstacleResponse) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                obstacles: vec![
+                    Obstacle::Circle { center: na::Point2::new(0.3, 0.3), radius: 0.15 },
+                    Obstacle::Rect { min: na::Point2::new(0.5, 0.5), max: na::Point2::new(0.8, 0.7) },
+                    Obstacle::Wall { from: na::Point2::new(0.1, 0.8), to: na::Point2::new(0.4, 0.9) },
+                ],
+                obstacle_response,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+
+            for _ in 0..120 {
+                sim.step(&mut rng, Some(50));
+
+                for obstacle in sim.world().obstacles() {
+                    assert!(sim.world().animals().iter().all(|animal| !obstacle.contains(animal.position())));
+                    assert!(sim.world().foods().iter().all(|food| !obstacle.contains(food.position())));
+                }
+            }
+        }
+    }
+
+    mod food_spawners {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                food_spawners: vec![
+                    FoodSpawner::Regrowth { delay: 50 },
+                    FoodSpawner::Patches { centers: vec![na::Point2::new(0.5, 0.5)], radius: 0.3 },
+                    FoodSpawner::Drift { speed: 0.001 },
+                    FoodSpawner::Seasons { period: 200, min_density: 0.5 },
+                    FoodSpawner::Nutrition { min: 0.5, max: 1.5 },
+                ],
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            let stats = sim.train(&mut rng, Some(300));
+
+            assert!(stats.max_fitness() > 0.0);
+
+            let foods = sim.world().foods();
+            assert_eq!(foods.len(), 60);
+            assert!(foods.iter().all(|food| (0.5..1.5).contains(&food.nutrition())));
+
+            let mut min_available = foods.len();
+
+            for _ in 0..200 {
+                sim.step(&mut rng, None);
+
+                let available = sim.world().foods().iter().filter(|food| food.is_available()).count();
+                min_available = min_available.min(available);
+            }
+
+            // At the worst time of the year only half of the food is out there
+            assert!(min_available <= 30);
+        }
+
+        #[test]
+        fn duplicated() {
+            let config = SimulationConfig {
+                food_spawners: vec![
+                    FoodSpawner::Regrowth { delay: 10 },
+                    FoodSpawner::Regrowth { delay: 20 },
+                ],
+                ..SimulationConfig::default()
+            };
+
+            let error = Simulation::new(config, &mut ChaCha8Rng::from_seed(Default::default())).err();
+            assert_eq!(error.as_deref(), Some("duplicated food spawner: Regrowth { delay: 20 }"));
+        }
+    }
+
+    mod evolve_eye {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                evolve_eye: true,
+                mutation_chance: 0.5,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            let brain_genes = Brain::random(&mut rng, &Eye::default()).as_chromosome().len();
+
+            for _ in 0..2 {
+                sim.train(&mut rng, Some(20));
+            }
+
+            let [min_range, max_range] = sim.config().fov_range_bounds;
+            let eyes: Vec<_> = sim.world().animals().iter().map(Animal::eye).collect();
+
+            assert!(eyes.iter().all(|eye| (min_range..=max_range).contains(&eye.fov_range())));
+            assert!(eyes.iter().any(|eye| eye.fov_range() != eyes[0].fov_range()));
+
+            for animal in sim.world().animals() {
+                assert_eq!(animal.as_chromosome(sim.config()).len(), brain_genes + EYE_GENES);
+            }
+        }
+
+        #[test]
+        fn vision_costs_energy() {
+            let energy_after = |vision_cost| {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let config = SimulationConfig {
+                    vision_cost,
+                    foods: 0,
+                    ..SimulationConfig::default()
+                };
+
+                let mut sim = Simulation::new(config, &mut rng).unwrap();
+
+                for _ in 0..10 {
+                    sim.step(&mut rng, None);
+                }
+
+                return sim.world().animals()[0].energy();
+            };
+
+            let area = Eye::default().area();
+            assert_relative_eq!(energy_after(0.0) - energy_after(0.01), 10.0 * 0.01 * area, epsilon = 1e-5);
+        }
+    }
+
+    mod evolve_body {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                evolve_eye: true,
+                evolve_body: true,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            let brain_genes = Brain::random(&mut rng, &Eye::default()).as_chromosome().len();
+
+            for _ in 0..2 {
+                sim.train(&mut rng, Some(20));
+            }
+
+            for _ in 0..10 {
+                sim.step(&mut rng, None);
+
+                for animal in sim.world().animals() {
+                    assert!(animal.speed <= animal.body().speed_max());
+                }
+            }
+
+            let [min_radius, max_radius] = sim.config().radius_bounds;
+            let bodies: Vec<_> = sim.world().animals().iter().map(Animal::body).collect();
+
+            assert!(bodies.iter().all(|body| (min_radius..=max_radius).contains(&body.radius())));
+            assert!(bodies.iter().all(|body| body.color().is_some()));
+            assert!(bodies.iter().any(|body| body.color() != bodies[0].color()));
+
+            for animal in sim.world().animals() {
+                assert_eq!(
+                    animal.as_chromosome(sim.config()).len(),
+                    brain_genes + EYE_GENES + BODY_GENES,
+                );
+            }
+        }
+
+        #[test]
+        fn invalid_bounds() {
+            let config = SimulationConfig {
+                radius_bounds: [0.02, 0.01],
+                ..SimulationConfig::default()
+            };
+
+            let error = Simulation::new(config, &mut ChaCha8Rng::from_seed(Default::default())).err();
+            assert_eq!(error.as_deref(), Some("invalid body bounds"));
+        }
+    }
+
+    mod map_elites {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random_flight_styles(&mut rng, 5);
+
+            for _ in 0..3 {
+                sim.train(&mut rng, Some(10));
+            }
+
+            let elites = sim.optimizer().elites().count();
+            assert!(elites > 1);
+
+            assert_eq!(sim.populate_from_elites(&mut rng), elites);
+            assert_eq!(sim.world().animals().len(), elites);
+            assert_eq!(sim.genealogy().current().len(), elites);
+        }
+    }
+
+    mod telemetry {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(&mut rng);
+
+            sim.collect_events(true);
+
+            for _ in 0..500 {
+                sim.step(&mut rng, Some(1000));
+            }
+
+            let mut last_meals = vec![None; sim.world().animals().len()];
+
+            for event in sim.drain_events() {
+                if let Event::Ate { time, animal, .. } = event {
+                    last_meals[animal.index] = Some(time);
+                }
+            }
+
+            assert!(last_meals.iter().any(Option::is_some));
+
+            for (animal, last_meal) in sim.world().animals().iter().zip(last_meals) {
+                let telemetry = animal.telemetry();
+
+                assert_relative_eq!(telemetry.avg_speed * 500.0, telemetry.distance, epsilon = 1e-4);
+                assert_eq!(telemetry.foods, animal.satiation);
+                assert_eq!(telemetry.turned, animal.turned);
+
+                // (animals eat before they move, which is when they get older)
+                assert_eq!(telemetry.since_meal, last_meal.map_or(500, |time| 500 - time));
+            }
+        }
+    }
+}
@@ -0,0 +1,513 @@
+use crate::*;
+use crate::config::ensure;
+
+/// Static obstacle that animals can't fly through or see through
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "shape", rename_all = "snake_case")
+)]
+pub enum Obstacle {
+    Circle {
+        center: na::Point2<f32>,
+        radius: f32,
+    },
+
+    /// Axis-aligned rectangle
+    Rect {
+        min: na::Point2<f32>,
+        max: na::Point2<f32>,
+    },
+
+    /// Line segment with no thickness
+    Wall {
+        from: na::Point2<f32>,
+        to: na::Point2<f32>,
+    },
+}
+
+/// What happens to an animal that flies into an obstacle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ObstacleResponse {
+    /// The animal slides along the obstacle
+    #[default]
+    Slide,
+
+    /// The animal bounces off the obstacle like a billiard ball
+    Bounce,
+}
+
+/// How far animals get pushed away from obstacles they've hit, so that they don't end up
+/// exactly on their edges
+const PUSH: f32 = 1e-5;
+
+impl Obstacle {
+    /// Returns an error if this obstacle is degenerate
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        return match *self {
+            Self::Circle { radius, .. } => ensure(radius > 0.0, "obstacle radius must be positive"),
+            Self::Rect { min, max } => ensure(min.x < max.x && min.y < max.y, "invalid obstacle bounds"),
+            Self::Wall { from, to } => ensure(from != to, "wall must have a non-zero length"),
+        };
+    }
+
+    /// Whether `point` lies inside this obstacle (walls don't have an inside)
+    pub fn contains(&self, point: na::Point2<f32>) -> bool {
+        return match *self {
+            Self::Circle { center, radius } => na::distance(&center, &point) < radius,
+
+            Self::Rect { min, max } => {
+                (min.x..=max.x).contains(&point.x) && (min.y..=max.y).contains(&point.y)
+            }
+
+            Self::Wall { .. } => false,
+        };
+    }
+
+    /// Point of this obstacle's outline that's closest to `point`
+    pub fn closest_point(&self, point: na::Point2<f32>) -> na::Point2<f32> {
+        match *self {
+            Self::Circle { center, radius } => {
+                let vec = point - center;
+
+                if vec.norm() == 0.0 {
+                    return center + na::Vector2::new(radius, 0.0);
+                }
+
+                return center + vec.normalize() * radius;
+            }
+
+            Self::Rect { min, max } => {
+                if !self.contains(point) {
+                    return na::Point2::new(point.x.clamp(min.x, max.x), point.y.clamp(min.y, max.y));
+                }
+
+                let (normal, depth) = rect_exit(min, max, point);
+                return point + normal * depth;
+            }
+
+            Self::Wall { from, to } => {
+                let wall = to - from;
+                let t = ((point - from).dot(&wall) / wall.norm_squared()).clamp(0.0, 1.0);
+
+                return from + wall * t;
+            }
+        }
+    }
+
+    /// Whether this obstacle stands between `from` and `to`
+    pub fn blocks(&self, from: na::Point2<f32>, to: na::Point2<f32>) -> bool {
+        return match *self {
+            Self::Circle { center, radius } => {
+                let segment = Self::Wall { from, to };
+                na::distance(&segment.closest_point(center), &center) < radius
+            }
+
+            Self::Rect { min, max } => {
+                let corners = [
+                    min,
+                    na::Point2::new(max.x, min.y),
+                    max,
+                    na::Point2::new(min.x, max.y),
+                ];
+
+                self.contains(from)
+                    || self.contains(to)
+                    || (0..4).any(|idx| segments_intersect(from, to, corners[idx], corners[(idx + 1) % 4]))
+            }
+
+            Self::Wall { from: a, to: b } => segments_intersect(from, to, a, b),
+        };
+    }
+
+    /// If an animal flying from `from` to `to` hits this obstacle, returns the direction in which
+    /// it should be pushed back (pointing out of the obstacle) and how far it got inside
+    pub(crate) fn penetration(
+        &self,
+        from: na::Point2<f32>,
+        to: na::Point2<f32>,
+    ) -> Option<(na::Vector2<f32>, f32)> {
+        match *self {
+            Self::Circle { center, radius } => {
+                let vec = to - center;
+                let dist = vec.norm();
+
+                if dist < radius {
+                    let normal = if dist > 0.0 {
+                        vec / dist
+                    } else {
+                        (from - to).try_normalize(0.0).unwrap_or_else(na::Vector2::x)
+                    };
+
+                    return Some((normal, radius - dist));
+                }
+
+                // `to` lies outside, but a fast animal might've flown right through the circle -
+                // if so, it gets pushed back behind the tangent at the point where it entered
+                let entry = circle_entry(center, radius, from, to)?;
+                let normal = (entry - center) / radius;
+                let depth = -(to - entry).dot(&normal);
+
+                return Some((normal, depth.max(0.0)));
+            }
+
+            Self::Rect { min, max } => {
+                let inside = min.x < to.x && to.x < max.x && min.y < to.y && to.y < max.y;
+
+                if inside {
+                    return Some(rect_exit(min, max, to));
+                }
+
+                // Same as for circles, the animal might've flown right through the rectangle
+                let (entry, normal) = rect_entry(min, max, from, to)?;
+                let depth = -(to - entry).dot(&normal);
+
+                return Some((normal, depth.max(0.0)));
+            }
+
+            Self::Wall { from: a, to: b } => {
+                if !segments_intersect(from, to, a, b) {
+                    return None;
+                }
+
+                let wall = b - a;
+                let mut normal = na::Vector2::new(-wall.y, wall.x).normalize();
+
+                if (from - a).dot(&normal) < 0.0 {
+                    normal = -normal;
+                }
+
+                let depth = -(to - a).dot(&normal);
+                return Some((normal, depth.max(0.0)));
+            }
+        }
+    }
+
+    /// Moves an animal that's just flown from `from` to `to` out of this obstacle, adjusting its
+    /// `rotation` if needed
+    pub(crate) fn collide(
+        &self,
+        from: na::Point2<f32>,
+        to: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        response: ObstacleResponse,
+    ) -> (na::Point2<f32>, na::Rotation2<f32>) {
+        let Some((normal, depth)) = self.penetration(from, to) else {
+            return (to, rotation);
+        };
+
+        match response {
+            ObstacleResponse::Slide => {
+                return (to + normal * (depth + PUSH), rotation);
+            }
+
+            ObstacleResponse::Bounce => {
+                let direction = rotation * na::Vector2::x();
+                let along_normal = direction.dot(&normal);
+
+                let rotation = if along_normal < 0.0 {
+                    trig::rotation(trig::direction(&(direction - 2.0 * along_normal * normal)))
+                } else {
+                    rotation
+                };
+
+                return (to + normal * (2.0 * depth + PUSH), rotation);
+            }
+        }
+    }
+}
+
+/// Shortest way out of a rectangle for a point inside it
+fn rect_exit(
+    min: na::Point2<f32>,
+    max: na::Point2<f32>,
+    point: na::Point2<f32>,
+) -> (na::Vector2<f32>, f32) {
+    let exits = [
+        (-na::Vector2::x(), point.x - min.x),
+        (na::Vector2::x(), max.x - point.x),
+        (-na::Vector2::y(), point.y - min.y),
+        (na::Vector2::y(), max.y - point.y),
+    ];
+
+    return exits
+        .into_iter()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap();
+}
+
+/// Point where the segment going from `from` to `to` enters a circle, if it does so at all
+fn circle_entry(
+    center: na::Point2<f32>,
+    radius: f32,
+    from: na::Point2<f32>,
+    to: na::Point2<f32>,
+) -> Option<na::Point2<f32>> {
+    let dir = to - from;
+    let offset = from - center;
+
+    let a = dir.norm_squared();
+    let b = offset.dot(&dir);
+    let c = offset.norm_squared() - radius * radius;
+
+    // Not moving at all, or starting inside the circle already
+    if a == 0.0 || c < 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - a * c;
+
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / a;
+
+    return if (0.0..=1.0).contains(&t) {
+        Some(from + dir * t)
+    } else {
+        None
+    };
+}
+
+/// Point where the segment going from `from` to `to` enters a rectangle, if it does so at all,
+/// together with the outward normal of the side it enters through
+fn rect_entry(
+    min: na::Point2<f32>,
+    max: na::Point2<f32>,
+    from: na::Point2<f32>,
+    to: na::Point2<f32>,
+) -> Option<(na::Point2<f32>, na::Vector2<f32>)> {
+    let dir = to - from;
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = na::Vector2::zeros();
+
+    for axis in 0..2 {
+        if dir[axis] == 0.0 {
+            if from[axis] <= min[axis] || from[axis] >= max[axis] {
+                return None;
+            }
+
+            continue;
+        }
+
+        let t1 = (min[axis] - from[axis]) / dir[axis];
+        let t2 = (max[axis] - from[axis]) / dir[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+        if near > t_enter {
+            t_enter = near;
+            normal = na::Vector2::zeros();
+            normal[axis] = -dir[axis].signum();
+        }
+
+        t_exit = t_exit.min(far);
+    }
+
+    if t_enter >= t_exit || !(0.0..=1.0).contains(&t_enter) {
+        return None;
+    }
+
+    return Some((from + dir * t_enter, normal));
+}
+
+fn segments_intersect(
+    p1: na::Point2<f32>,
+    p2: na::Point2<f32>,
+    q1: na::Point2<f32>,
+    q2: na::Point2<f32>,
+) -> bool {
+    let cross = |a: na::Vector2<f32>, b: na::Vector2<f32>| a.perp(&b);
+
+    let d1 = cross(q2 - q1, p1 - q1);
+    let d2 = cross(q2 - q1, p2 - q1);
+    let d3 = cross(p2 - p1, q1 - p1);
+    let d4 = cross(p2 - p1, q2 - p1);
+
+    let collinear = d1 == 0.0 && d2 == 0.0;
+
+    return !collinear && d1 * d2 <= 0.0 && d3 * d4 <= 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use super::*;
+
+    fn point(x: f32, y: f32) -> na::Point2<f32> {
+        return na::Point2::new(x, y);
+    }
+
+    fn circle() -> Obstacle {
+        return Obstacle::Circle { center: point(0.5, 0.5), radius: 0.1 };
+    }
+
+    fn rect() -> Obstacle {
+        return Obstacle::Rect { min: point(0.4, 0.4), max: point(0.6, 0.6) };
+    }
+
+    fn wall() -> Obstacle {
+        return Obstacle::Wall { from: point(0.5, 0.0), to: point(0.5, 1.0) };
+    }
+
+    mod validate {
+        use super::*;
+        use test_case::test_case;
+
+        #[test]
+        fn test() {
+            for obstacle in [circle(), rect(), wall()] {
+                assert_eq!(obstacle.validate(), Ok(()));
+            }
+        }
+
+        #[test_case(
+            Obstacle::Circle { center: point(0.5, 0.5), radius: 0.0 },
+            "obstacle radius must be positive" ; "zero radius"
+        )]
+        #[test_case(
+            Obstacle::Rect { min: point(0.6, 0.4), max: point(0.4, 0.6) },
+            "invalid obstacle bounds" ; "inverted rect"
+        )]
+        #[test_case(
+            Obstacle::Rect { min: point(0.4, 0.4), max: point(0.6, 0.4) },
+            "invalid obstacle bounds" ; "flat rect"
+        )]
+        #[test_case(
+            Obstacle::Wall { from: point(0.5, 0.5), to: point(0.5, 0.5) },
+            "wall must have a non-zero length" ; "zero-length wall"
+        )]
+        fn invalid(obstacle: Obstacle, expected: &str) {
+            assert_eq!(obstacle.validate(), Err(expected.to_string()));
+        }
+    }
+
+    mod contains {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert!(circle().contains(point(0.55, 0.55)));
+            assert!(!circle().contains(point(0.6, 0.6)));
+
+            assert!(rect().contains(point(0.59, 0.41)));
+            assert!(!rect().contains(point(0.61, 0.5)));
+
+            assert!(!wall().contains(point(0.5, 0.5)));
+        }
+    }
+
+    mod blocks {
+        use super::*;
+
+        #[test]
+        fn test() {
+            for obstacle in [circle(), rect(), wall()] {
+                assert!(obstacle.blocks(point(0.2, 0.5), point(0.8, 0.5)), "{obstacle:?}");
+                assert!(!obstacle.blocks(point(0.2, 0.5), point(0.3, 0.5)), "{obstacle:?}");
+            }
+
+            assert!(!circle().blocks(point(0.2, 0.3), point(0.8, 0.3)));
+            assert!(!rect().blocks(point(0.2, 0.3), point(0.8, 0.3)));
+            assert!(wall().blocks(point(0.2, 0.3), point(0.8, 0.3)));
+        }
+    }
+
+    mod closest_point {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_relative_eq!(circle().closest_point(point(0.9, 0.5)), point(0.6, 0.5));
+            assert_relative_eq!(rect().closest_point(point(0.9, 0.5)), point(0.6, 0.5));
+            assert_relative_eq!(rect().closest_point(point(0.5, 0.42)), point(0.5, 0.4));
+            assert_relative_eq!(wall().closest_point(point(0.9, 0.25)), point(0.5, 0.25));
+            assert_relative_eq!(wall().closest_point(point(0.9, 1.5)), point(0.5, 1.0));
+        }
+    }
+
+    mod collide {
+        use super::*;
+
+        #[test]
+        fn slide() {
+            // Flying diagonally into the obstacles' left sides
+            let cases = [
+                (circle(), point(0.39, 0.49)),
+                (rect(), point(0.39, 0.45)),
+                (wall(), point(0.49, 0.45)),
+            ];
+
+            for (obstacle, from) in cases {
+                let to = from + na::Vector2::new(0.02, 0.02);
+                let rotation = trig::rotation(FRAC_PI_4);
+
+                let (position, new_rotation) = obstacle.collide(from, to, rotation, ObstacleResponse::Slide);
+
+                assert!(!obstacle.contains(position), "{obstacle:?}");
+                assert!(!obstacle.blocks(from, position), "{obstacle:?}");
+                assert!(position.y > from.y, "{obstacle:?}");
+                assert_eq!(new_rotation, rotation);
+            }
+        }
+
+        #[test]
+        fn bounce() {
+            let from = point(0.48, 0.3);
+            let to = point(0.52, 0.3);
+
+            let (position, rotation) = wall().collide(from, to, trig::rotation(0.0), ObstacleResponse::Bounce);
+
+            assert_relative_eq!(position.x, 0.48, epsilon = 1e-4);
+            assert_relative_eq!(position.y, 0.3);
+            assert_relative_eq!(trig::angle(&rotation).abs(), PI, epsilon = 1e-5);
+        }
+
+        #[test]
+        fn bounce_off_circle() {
+            let from = point(0.5, 0.35);
+            let to = point(0.5, 0.41);
+
+            let (position, rotation) = circle().collide(from, to, trig::rotation(FRAC_PI_2), ObstacleResponse::Bounce);
+
+            assert_relative_eq!(position.y, 0.39, epsilon = 1e-4);
+            assert_relative_eq!(trig::angle(&rotation), -FRAC_PI_2, epsilon = 1e-5);
+        }
+
+        #[test]
+        fn fly_through() {
+            // Flying so fast that the animal would end up on the other side of the obstacles
+            let from = point(0.3, 0.5);
+            let to = point(0.7, 0.5);
+
+            for obstacle in [circle(), rect(), wall()] {
+                for response in [ObstacleResponse::Slide, ObstacleResponse::Bounce] {
+                    let (position, _) = obstacle.collide(from, to, trig::rotation(0.0), response);
+
+                    assert!(position.x < 0.5, "{obstacle:?}");
+                    assert!(!obstacle.contains(position), "{obstacle:?}");
+                    assert!(!obstacle.blocks(from, position), "{obstacle:?}");
+                }
+            }
+        }
+
+        #[test]
+        fn miss() {
+            for obstacle in [circle(), rect(), wall()] {
+                let from = point(0.1, 0.1);
+                let to = point(0.12, 0.1);
+
+                assert_eq!(obstacle.collide(from, to, trig::rotation(0.0), ObstacleResponse::Bounce).0, to);
+            }
+        }
+    }
+}
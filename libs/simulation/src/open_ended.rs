@@ -0,0 +1,311 @@
+use ga::{CrossoverMethod, MutationMethod};
+
+use crate::*;
+
+/// Births and deaths in the open-ended mode, counted since the last report
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Census {
+    pub(crate) born: usize,
+    pub(crate) immigrated: usize,
+    pub(crate) caught: usize,
+    pub(crate) starved: usize,
+    pub(crate) died_of_age: usize,
+}
+
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Open-ended counterpart of `step()`: instead of replacing the whole population at the
+    /// end of a generation, birds live for up to `lifespan` steps, and each bird that has eaten
+    /// `foods_per_offspring` foods (and has `offspring_energy` to spare) gives birth to a
+    /// mutated offspring right next to itself - crossed over with the closest bird it sees, if
+    /// there's any.
+    ///
+    /// Dead birds are removed from the world, so its population floats within
+    /// `population_bounds`. Every `interval` steps returns statistics of the living birds, with
+    /// births and deaths counted since the previous report.
+    ///
+    /// Predators, if there are any, don't reproduce in this mode.
+    pub fn step_open_ended(&mut self, rng: &mut dyn RngCore, interval: usize) -> Option<Statistics> {
+        self.process_world(rng);
+
+        self.process_deaths();
+        self.process_births(rng);
+        self.process_immigration(rng);
+
+        self.age += 1;
+        if self.age >= interval {
+            self.age = 0;
+
+            let population: Vec<_> = self.world
+                .animals
+                .iter()
+                .map(|animal| AnimalIndividual::from_animal(animal, &self.config))
+                .collect();
+
+            let mut stats = Statistics::new(ga::Statistics::new(&population), &self.world.animals, &self.config);
+            stats.set_census(std::mem::take(&mut self.census));
+            stats.set_heatmaps(self.take_heatmaps());
+            return Some(stats);
+        }
+        return None;
+    }
+
+    fn process_deaths(&mut self) {
+        let time = self.now();
+        let mut dead = Vec::new();
+
+        for (idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if animal.alive && animal.age >= self.config.lifespan {
+                animal.alive = false;
+                self.census.died_of_age += 1;
+
+                self.events.emit(|| Event::Died {
+                    time,
+                    animal: AnimalRef::new(Species::Prey, idx, &self.genealogy),
+                    cause: Death::Age,
+                });
+            } else if animal.caught {
+                self.census.caught += 1;
+            } else if !animal.alive {
+                self.census.starved += 1;
+            }
+
+            if !animal.alive {
+                dead.push(idx);
+            }
+        }
+
+        self.genealogy.record_deaths(&dead);
+        self.world.animals.retain(Animal::is_alive);
+    }
+
+    fn process_births(&mut self, rng: &mut dyn RngCore) {
+        let [_, max_population] = self.config.population_bounds;
+        let time = self.now();
+
+        // Birds have moved since `process_collisions()`, and some of them are gone
+        self.prey_index.rebuild(self.world.animals.iter().map(Animal::position));
+
+        let crossover = ga::UniformCrossover::new();
+        let mutation = self.config.mutation();
+        let mut children = Vec::new();
+
+        for idx in 0..self.world.animals.len() {
+            if self.world.animals.len() + children.len() >= max_population {
+                break;
+            }
+
+            if !self.is_fertile(&self.world.animals[idx]) {
+                continue;
+            }
+
+            let partner = self.partner_of(idx);
+            let parent_a = self.world.animals[idx].as_chromosome(&self.config);
+            let parent_b = self.world.animals[partner].as_chromosome(&self.config);
+
+            let mut chromosome = crossover.crossover(rng, &parent_a, &parent_b);
+            let crossed = chromosome.clone();
+            mutation.mutate(rng, &mut chromosome);
+
+            let parents = [idx, partner].map(|parent| AnimalRef::new(Species::Prey, parent, &self.genealogy));
+
+            let id = self.genealogy.record_child(
+                [idx, partner],
+                crossed.iter().zip(parent_a.iter()).filter(|(c, p)| c == p).count(),
+                crossed.iter().zip(chromosome.iter()).filter(|(c, m)| c != m).count(),
+            );
+
+            self.events.emit(|| Event::Born {
+                time,
+                animal: AnimalRef {
+                    species: Species::Prey,
+                    index: self.world.animals.len() + children.len(),
+                    id: Some(id),
+                },
+                parents,
+            });
+
+            let mut child = Animal::from_chromosome(Species::Prey, chromosome, rng, &self.config);
+
+            child.position = self.birthplace(self.world.animals[idx].position, rng);
+            child.energy = self.offspring_energy();
+
+            let parent = &mut self.world.animals[idx];
+            parent.offspring += 1;
+            parent.spend_energy(child.energy, &self.config);
+
+            children.push(child);
+        }
+
+        self.census.born += children.len();
+        self.world.animals.extend(children);
+    }
+
+    /// Keeps the population from dropping below `population_bounds`
+    fn process_immigration(&mut self, rng: &mut dyn RngCore) {
+        let [min_population, _] = self.config.population_bounds;
+        let missing = min_population.saturating_sub(self.world.animals.len());
+
+        for _ in 0..missing {
+            self.world.animals.push(Animal::random(rng, &self.config));
+        }
+
+        self.genealogy.record_immigrants(missing);
+        self.census.immigrated += missing;
+
+        let time = self.now();
+
+        for idx in self.world.animals.len() - missing..self.world.animals.len() {
+            self.events.emit(|| Event::Immigrated {
+                time,
+                animal: AnimalRef::new(Species::Prey, idx, &self.genealogy),
+            });
+        }
+    }
+
+    fn is_fertile(&self, animal: &Animal) -> bool {
+        let foods_needed = (animal.offspring + 1) * self.config.foods_per_offspring;
+
+        return animal.alive
+            && animal.satiation >= foods_needed
+            && animal.energy >= self.offspring_energy();
+    }
+
+    fn offspring_energy(&self) -> f32 {
+        return self.config.offspring_energy * self.config.energy_max;
+    }
+
+    /// Index of the closest bird the `idx`-th bird can see, or `idx` itself if there's none
+    fn partner_of(&self, idx: usize) -> usize {
+        let animal = &self.world.animals[idx];
+        let range = animal.eye.fov_range;
+
+        return self.prey_index
+            .query(animal.position, range)
+            .into_iter()
+            .filter(|&other| other != idx)
+            .map(|other| (other, self.config.topology.distance(animal.position, self.world.animals[other].position)))
+            .filter(|&(_, distance)| distance <= range)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(idx, |(other, _)| other);
+    }
+
+    /// Random position within `offspring_distance` from the parent, outside of obstacles
+    fn birthplace(&self, parent: na::Point2<f32>, rng: &mut dyn RngCore) -> na::Point2<f32> {
+        let distance = self.config.offspring_distance * rng.gen::<f32>();
+        let position = parent + trig::random_rotation(rng) * na::Vector2::new(distance, 0.0);
+        let (position, _) = self.config.topology.constrain(position, na::Rotation2::identity());
+
+        if self.world.obstacles.iter().any(|obstacle| obstacle.contains(position)) {
+            return parent;
+        }
+
+        return position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn config() -> SimulationConfig {
+        return SimulationConfig {
+            animals: 20,
+            lifespan: 300,
+            foods_per_offspring: 1,
+            population_bounds: [10, 40],
+            ..SimulationConfig::default()
+        };
+    }
+
+    mod step_open_ended {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::new(config(), &mut rng).unwrap();
+
+            let mut born = 0;
+            let mut died_of_age = 0;
+
+            for _ in 0..1000 {
+                if let Some(stats) = sim.step_open_ended(&mut rng, 100) {
+                    born += stats.born();
+                    died_of_age += stats.died_of_age();
+                }
+
+                let animals = sim.world().animals();
+
+                assert!((10..=40).contains(&animals.len()));
+                assert!(animals.iter().all(|animal| animal.is_alive() && animal.age() < 300));
+                assert_eq!(sim.genealogy().current().len(), animals.len());
+            }
+
+            assert!(born > 0);
+            assert!(died_of_age > 0);
+
+            // Some of the living birds should've been born in the world
+            assert!(sim
+                .world()
+                .animals()
+                .iter()
+                .any(|animal| sim.ancestry(animal).unwrap().len() > 1));
+        }
+
+        #[test]
+        fn immigration() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                lifespan: 10,
+                foods: 0,
+                ..config()
+            };
+
+            let mut sim = Simulation::new(config, &mut rng).unwrap();
+            let mut stats = None;
+
+            while stats.is_none() {
+                stats = sim.step_open_ended(&mut rng, 15);
+            }
+
+            let stats = stats.unwrap();
+
+            assert_eq!(stats.born(), 0);
+            assert_eq!(stats.died_of_age(), 20);
+            assert_eq!(stats.immigrated(), 10);
+            assert_eq!(sim.world().animals().len(), 10);
+        }
+    }
+
+    mod is_fertile {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let sim = Simulation::new(SimulationConfig::default(), &mut rng).unwrap();
+            let mut animal = Animal::random(&mut rng, sim.config());
+
+            animal.satiation = 2;
+            assert!(!sim.is_fertile(&animal));
+
+            animal.satiation = 3;
+            assert!(sim.is_fertile(&animal));
+
+            animal.offspring = 1;
+            assert!(!sim.is_fertile(&animal));
+
+            animal.satiation = 6;
+            animal.energy = 0.4;
+            assert!(!sim.is_fertile(&animal));
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// How often `Replay` keeps snapshots of the simulation, which is what makes scrubbing backward
+/// cheap
+const KEYFRAME_INTERVAL: usize = 100;
+
+/// Compact recording of a `SeededSimulation`: its starting point followed by the inputs of each
+/// step - since seeded simulations are deterministic, that's enough to recreate every single
+/// frame (see `Replay`).
+///
+/// Created by `SeededSimulation::start_recording()`; only simulations evolved by the default
+/// optimizer replay faithfully, and changes made via `SeededSimulation::simulation_mut()` aren't
+/// recorded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recorder {
+    start: Start,
+    steps: Vec<Steps>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Start {
+    /// Recording started right after the simulation had been created
+    Seed {
+        seed: u64,
+        config: Box<SimulationConfig>,
+    },
+
+    /// Recording started in the middle of the simulation
+    Snapshot {
+        snapshot: Box<Snapshot>,
+    },
+}
+
+/// Run of steps with the same `generation_length`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Steps {
+    generation_length: Option<usize>,
+    count: usize,
+}
+
+impl Recorder {
+    fn new<O>(sim: &SeededSimulation<O>) -> Self
+        where
+            O: ga::Optimizer<AnimalIndividual>
+    {
+        let start = if sim.sim.time == 0 {
+            Start::Seed { seed: sim.seed, config: Box::new(sim.sim.config.clone()) }
+        } else {
+            Start::Snapshot { snapshot: Box::new(sim.snapshot()) }
+        };
+
+        return Self { start, steps: Vec::new() };
+    }
+
+    pub(crate) fn record(&mut self, generation_length: Option<usize>) {
+        if let Some(steps) = self.steps.last_mut() {
+            if steps.generation_length == generation_length {
+                steps.count += 1;
+                return;
+            }
+        }
+
+        self.steps.push(Steps { generation_length, count: 1 });
+    }
+
+    /// Number of recorded steps
+    pub fn len(&self) -> usize {
+        return self.steps.iter().map(|steps| steps.count).sum();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.steps.is_empty();
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).expect("recording is always serializable");
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        return serde_json::from_str(json);
+    }
+
+    fn simulation(&self) -> Result<SeededSimulation, String> {
+        return match &self.start {
+            Start::Seed { seed, config } => Simulation::from_seed(*seed, (**config).clone()),
+            Start::Snapshot { snapshot } => SeededSimulation::restore((**snapshot).clone()),
+        };
+    }
+}
+
+impl<O> SeededSimulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Starts recording each step of this simulation (see `Recorder`), discarding the previous
+    /// recording, if any
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::new(self));
+    }
+
+    /// Stops recording and returns what's been recorded so far
+    pub fn stop_recording(&mut self) -> Option<Recorder> {
+        return self.recorder.take();
+    }
+
+    pub fn recorder(&self) -> Option<&Recorder> {
+        return self.recorder.as_ref();
+    }
+}
+
+/// Plays a recording back step by step, producing `World` frames - forward and backward alike.
+///
+/// Frames are recreated by running the recorded simulation again; scrubbing backward restores
+/// the closest snapshot taken on the way and simulates the rest.
+pub struct Replay {
+    /// `generation_length` of each step
+    inputs: Vec<Option<usize>>,
+    sim: SeededSimulation,
+    /// Index of the current frame - 0 is the world before the first recorded step
+    position: usize,
+    /// Taken at frames 0, `KEYFRAME_INTERVAL`, 2 * `KEYFRAME_INTERVAL` and so on, as the replay
+    /// goes forward
+    keyframes: Vec<Keyframe>,
+    /// The longest genealogy seen so far - genealogies of all the keyframes are its prefixes
+    genealogy: ga::Genealogy,
+}
+
+/// Snapshot taken on the way, except for its ever-growing genealogy - that's kept just once, in
+/// `Replay::genealogy`, since records of a genealogy are only ever appended
+struct Keyframe {
+    snapshot: Snapshot,
+    /// `Genealogy::len()` at the keyframe
+    genealogy_len: usize,
+    /// `Genealogy::current()` at the keyframe
+    current: Vec<ga::IndividualId>,
+}
+
+impl Keyframe {
+    fn new(sim: &SeededSimulation) -> Self {
+        return Self {
+            snapshot: sim.snapshot_without_genealogy(),
+            genealogy_len: sim.sim.genealogy.len(),
+            current: sim.sim.genealogy.current().to_vec(),
+        };
+    }
+}
+
+impl Replay {
+    /// Fails if the recording's config is invalid
+    pub fn new(recorder: &Recorder) -> Result<Self, String> {
+        let inputs = recorder
+            .steps
+            .iter()
+            .flat_map(|steps| std::iter::repeat_n(steps.generation_length, steps.count))
+            .collect();
+
+        let sim = recorder.simulation()?;
+        let keyframes = vec![Keyframe::new(&sim)];
+
+        return Ok(Self {
+            inputs,
+            sim,
+            position: 0,
+            keyframes,
+            genealogy: ga::Genealogy::default(),
+        });
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let recorder = Recorder::from_json(json)?;
+        return Self::new(&recorder).map_err(serde::de::Error::custom);
+    }
+
+    /// Number of steps - the last frame is at `len()`
+    pub fn len(&self) -> usize {
+        return self.inputs.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.inputs.is_empty();
+    }
+
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.sim.seed();
+    }
+
+    /// The current frame
+    pub fn world(&self) -> &World {
+        return self.sim.world();
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        return self.sim.simulation();
+    }
+
+    /// Stops the replay, returning the simulation at the current frame
+    pub fn into_simulation(self) -> Simulation {
+        return self.sim.into_parts().0;
+    }
+
+    /// Goes to the next frame, returning the statistics if a generation ended on the way;
+    /// does nothing at the end of the replay
+    pub fn step_forward(&mut self) -> Option<Statistics> {
+        let generation_length = *self.inputs.get(self.position)?;
+        let stats = self.sim.step(generation_length);
+
+        self.position += 1;
+
+        if self.position == self.keyframes.len() * KEYFRAME_INTERVAL {
+            self.keyframes.push(Keyframe::new(&self.sim));
+        }
+
+        return stats;
+    }
+
+    /// Goes to the previous frame; does nothing at the beginning of the replay
+    pub fn step_backward(&mut self) {
+        self.seek(self.position.saturating_sub(1));
+    }
+
+    /// Goes to the given frame (clamped to `len()`)
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.len());
+        let keyframe = (position / KEYFRAME_INTERVAL).min(self.keyframes.len() - 1);
+
+        if position < self.position || keyframe * KEYFRAME_INTERVAL > self.position {
+            let genealogy = std::mem::take(&mut self.sim.sim.genealogy);
+
+            if genealogy.len() > self.genealogy.len() {
+                self.genealogy = genealogy;
+            }
+
+            let Keyframe { snapshot, genealogy_len, current } = &self.keyframes[keyframe];
+
+            self.sim = SeededSimulation::restore(snapshot.clone())
+                .expect("keyframes come from a valid simulation");
+
+            self.sim.sim.genealogy = self.genealogy.rewound(*genealogy_len, current);
+            self.position = keyframe * KEYFRAME_INTERVAL;
+        }
+
+        while self.position < position {
+            self.step_forward();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SimulationConfig {
+        return SimulationConfig {
+            generation_length: 60,
+            ..SimulationConfig::default()
+        };
+    }
+
+    fn positions(world: &World) -> Vec<na::Point2<f32>> {
+        return world.animals().iter().map(Animal::position).collect();
+    }
+
+    mod recorder {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut sim = Simulation::from_seed(1, config()).unwrap();
+            sim.start_recording();
+
+            sim.train(None);
+            sim.step(Some(10));
+            sim.step(Some(10));
+            sim.step(None);
+
+            let recorder = sim.stop_recording().unwrap();
+
+            assert_eq!(recorder.len(), 64);
+            assert_eq!(recorder.steps, vec![
+                Steps { generation_length: None, count: 61 },
+                Steps { generation_length: Some(10), count: 2 },
+                Steps { generation_length: None, count: 1 },
+            ]);
+
+            assert!(sim.recorder().is_none());
+        }
+    }
+
+    mod replay {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut sim = Simulation::from_seed(1, config()).unwrap();
+            sim.start_recording();
+
+            let mut frames = vec![positions(sim.world())];
+
+            for _ in 0..250 {
+                sim.step(None);
+                frames.push(positions(sim.world()));
+            }
+
+            let json = sim.stop_recording().unwrap().to_json();
+            let mut replay = Replay::from_json(&json).unwrap();
+
+            assert_eq!(replay.len(), 250);
+
+            while replay.position() < replay.len() {
+                replay.step_forward();
+                assert_eq!(positions(replay.world()), frames[replay.position()]);
+            }
+
+            // Going past the end does nothing
+            assert!(replay.step_forward().is_none());
+            assert_eq!(replay.position(), 250);
+
+            for position in [249, 120, 0, 199, 200, 30] {
+                replay.seek(position);
+
+                assert_eq!(replay.position(), position);
+                assert_eq!(positions(replay.world()), frames[position]);
+            }
+
+            replay.step_backward();
+            assert_eq!(positions(replay.world()), frames[29]);
+        }
+
+        #[test]
+        fn genealogy() {
+            let mut sim = Simulation::from_seed(1, config()).unwrap();
+            sim.start_recording();
+
+            let genealogy = |sim: &Simulation| {
+                return (sim.genealogy().len(), sim.genealogy().current().to_vec());
+            };
+
+            let mut frames = vec![genealogy(sim.simulation())];
+
+            for _ in 0..250 {
+                sim.step(None);
+                frames.push(genealogy(sim.simulation()));
+            }
+
+            let mut replay = Replay::new(&sim.stop_recording().unwrap()).unwrap();
+
+            for position in [250, 130, 0, 249, 60, 61] {
+                replay.seek(position);
+                assert_eq!(genealogy(replay.simulation()), frames[position]);
+            }
+
+            // Ancestry of the current birds is still complete
+            let last = sim.simulation().genealogy().records().last().unwrap().id;
+            replay.seek(250);
+
+            assert_eq!(
+                replay.simulation().genealogy().ancestry(last),
+                sim.simulation().genealogy().ancestry(last),
+            );
+        }
+
+        #[test]
+        fn from_the_middle() {
+            let mut sim = Simulation::from_seed(1, config()).unwrap();
+
+            sim.train(None);
+            sim.start_recording();
+            sim.train(None);
+
+            let mut replay = Replay::new(&sim.stop_recording().unwrap()).unwrap();
+            replay.seek(replay.len());
+
+            assert_eq!(positions(replay.world()), positions(sim.world()));
+        }
+    }
+}
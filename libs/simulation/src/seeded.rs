@@ -0,0 +1,196 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::*;
+
+/// A `Simulation` bundled together with the random number generator driving it, created by
+/// `Simulation::from_seed()`.
+///
+/// Two seeded simulations created from the same seed and config go through exactly the same
+/// worlds and statistics, as long as they are driven by the same calls.
+///
+/// The simulation sticks to platform-independent math (see `trig`), so runs are meant to match
+/// between native and wasm builds as well - but only native runs are checked against the golden
+/// values in the tests below.
+///
+/// (this doesn't hold for `Simulation::cma_es()` and `Simulation::mu_lambda_es()`, which sample
+/// normal distributions using the platform's `exp()` and `ln()` - those are reproducible only on
+/// the same platform.)
+pub struct SeededSimulation<O = DefaultOptimizer> {
+    pub(crate) seed: u64,
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) sim: Simulation<O>,
+    /// Present while the simulation is being recorded (see `start_recording()`)
+    #[cfg(feature = "serde")]
+    pub(crate) recorder: Option<Recorder>,
+}
+
+impl Simulation {
+    /// Fails if `config` is invalid
+    pub fn from_seed(seed: u64, config: SimulationConfig) -> Result<SeededSimulation, String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let sim = Simulation::new(config, &mut rng)?;
+
+        return Ok(SeededSimulation {
+            seed,
+            rng,
+            sim,
+            #[cfg(feature = "serde")]
+            recorder: None,
+        });
+    }
+}
+
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Fails if `config` is invalid
+    pub fn from_seed_with_optimizer(
+        seed: u64,
+        config: SimulationConfig,
+        optimizer: O,
+    ) -> Result<SeededSimulation<O>, String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let sim = Simulation::new_with_optimizer(config, &mut rng, optimizer)?;
+
+        return Ok(SeededSimulation {
+            seed,
+            rng,
+            sim,
+            #[cfg(feature = "serde")]
+            recorder: None,
+        });
+    }
+}
+
+impl<O> SeededSimulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn simulation(&self) -> &Simulation<O> {
+        return &self.sim;
+    }
+
+    pub fn simulation_mut(&mut self) -> &mut Simulation<O> {
+        return &mut self.sim;
+    }
+
+    pub fn world(&self) -> &World {
+        return self.sim.world();
+    }
+
+    /// See `Simulation::step()`
+    pub fn step(&mut self, generation_length: Option<usize>) -> Option<Statistics> {
+        #[cfg(feature = "serde")]
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(generation_length);
+        }
+
+        return self.sim.step(&mut self.rng, generation_length);
+    }
+
+    /// See `Simulation::train()`
+    pub fn train(&mut self, generation_length: Option<usize>) -> Statistics {
+        // (going step by step, so that the recorder sees each of them)
+        loop {
+            if let Some(stats) = self.step(generation_length) {
+                return stats;
+            }
+        }
+    }
+
+    /// Splits this simulation into the underlying simulation and the generator driving it, e.g.
+    /// to call methods that require an explicit `rng`
+    pub fn into_parts(self) -> (Simulation<O>, ChaCha8Rng) {
+        return (self.sim, self.rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SimulationConfig {
+        return SimulationConfig {
+            generation_length: 100,
+            ..SimulationConfig::default()
+        };
+    }
+
+    /// Everything observable about a simulation, bit by bit
+    fn snapshot(sim: &SeededSimulation) -> Vec<u32> {
+        let animals = sim.world().animals().iter().flat_map(|animal| {
+            return [animal.position().x, animal.position().y, trig::angle(&animal.rotation())]
+                .into_iter()
+                .chain(animal.as_chromosome(sim.simulation().config()));
+        });
+
+        let foods = sim.world().foods().iter().flat_map(|food| {
+            return [food.position().x, food.position().y];
+        });
+
+        return animals.chain(foods).map(f32::to_bits).collect();
+    }
+
+    fn fitness(stats: &Statistics) -> [f32; 2] {
+        return [stats.max_fitness(), stats.avg_fitness()];
+    }
+
+    mod from_seed {
+        use super::*;
+
+        #[test]
+        fn same_seed() {
+            let mut a = Simulation::from_seed(42, config()).unwrap();
+            let mut b = Simulation::from_seed(42, config()).unwrap();
+
+            assert_eq!(snapshot(&a), snapshot(&b));
+
+            for _ in 0..3 {
+                assert_eq!(fitness(&a.train(None)), fitness(&b.train(None)));
+                assert_eq!(snapshot(&a), snapshot(&b));
+            }
+        }
+
+        #[test]
+        fn different_seeds() {
+            let a = Simulation::from_seed(42, config()).unwrap();
+            let b = Simulation::from_seed(43, config()).unwrap();
+
+            assert_ne!(snapshot(&a), snapshot(&b));
+        }
+    }
+
+    /// These values were recorded once and must not change - if they do, runs shared by users
+    /// (e.g. "seed 42 evolves great birds") aren't reproducible anymore
+    mod golden {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut sim = Simulation::from_seed(42, config()).unwrap();
+
+            let actual: Vec<_> = (0..5)
+                .map(|_| fitness(&sim.train(None)))
+                .collect();
+
+            let expected = vec![
+                [3.0, 0.625],
+                [4.0, 0.75],
+                [3.0, 0.6],
+                [4.0, 0.7],
+                [2.0, 0.675],
+            ];
+
+            assert_eq!(actual, expected);
+
+            let position = sim.world().animals()[0].position();
+            assert_eq!([position.x, position.y], [0.6240403, 0.29003513]);
+        }
+    }
+}
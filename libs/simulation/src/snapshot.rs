@@ -0,0 +1,224 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Complete state of a simulation - config, world, genealogy and so on - created by
+/// `Simulation::snapshot()`, e.g. to save it to disk or to share an interesting world.
+///
+/// State of the optimizer itself (e.g. MAP-Elites' archive or CMA-ES' distribution), novelty
+/// search and custom behavior descriptors aren't part of the snapshot - restored simulations
+/// start with fresh ones.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    config: SimulationConfig,
+    world: World,
+    genealogy: ga::Genealogy,
+    census: Census,
+    age: usize,
+    time: usize,
+    /// Heatmaps of the current generation, if enabled
+    heatmaps: Option<Heatmaps>,
+    /// Present only for snapshots of `SeededSimulation`s
+    seed: Option<u64>,
+    /// Present only for snapshots of `SeededSimulation`s
+    rng: Option<ChaCha8Rng>,
+}
+
+impl Snapshot {
+    pub fn config(&self) -> &SimulationConfig {
+        return &self.config;
+    }
+
+    pub fn world(&self) -> &World {
+        return &self.world;
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).expect("snapshot is always serializable");
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let snapshot: Self = serde_json::from_str(json)?;
+
+        if snapshot.genealogy.current().len() != snapshot.world.animals.len() {
+            return Err(serde::de::Error::custom("genealogy doesn't match the world"));
+        }
+
+        return Ok(snapshot);
+    }
+}
+
+impl<O> Simulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    pub fn snapshot(&self) -> Snapshot {
+        return Snapshot {
+            genealogy: self.genealogy.clone(),
+            ..self.snapshot_without_genealogy()
+        };
+    }
+
+    /// Same as `snapshot()`, but with an empty genealogy - which is the part of the snapshot
+    /// that keeps on growing (see `Replay`)
+    pub(crate) fn snapshot_without_genealogy(&self) -> Snapshot {
+        return Snapshot {
+            config: self.config.clone(),
+            world: self.world.clone(),
+            genealogy: ga::Genealogy::default(),
+            census: self.census,
+            age: self.age,
+            time: self.time,
+            heatmaps: self.heatmaps.clone(),
+            seed: None,
+            rng: None,
+        };
+    }
+
+    /// Same as `restore()`, but evolves the simulation using `optimizer` from now on
+    pub fn restore_with_optimizer(snapshot: Snapshot, optimizer: O) -> Result<Self, String> {
+        snapshot.config.validate()?;
+
+        let mut sim = Self::from_parts(snapshot.config, snapshot.world, snapshot.genealogy, optimizer);
+
+        sim.census = snapshot.census;
+        sim.age = snapshot.age;
+        sim.time = snapshot.time;
+        sim.heatmaps = snapshot.heatmaps;
+
+        return Ok(sim);
+    }
+}
+
+impl Simulation {
+    /// Recreates a simulation from its snapshot, evolved by the default optimizer; fails if the
+    /// snapshot's config is invalid
+    pub fn restore(snapshot: Snapshot) -> Result<Self, String> {
+        snapshot.config.validate()?;
+
+        let ga = snapshot.config.genetic_algorithm();
+        return Self::restore_with_optimizer(snapshot, ga);
+    }
+}
+
+impl<O> SeededSimulation<O>
+    where
+        O: ga::Optimizer<AnimalIndividual>
+{
+    /// Same as `Simulation::snapshot()`, but includes the seed and the state of the generator,
+    /// so that the restored simulation continues exactly the same way this one would
+    pub fn snapshot(&self) -> Snapshot {
+        return Snapshot {
+            genealogy: self.sim.genealogy.clone(),
+            ..self.snapshot_without_genealogy()
+        };
+    }
+
+    /// Same as `snapshot()`, but with an empty genealogy (see `Replay`)
+    pub(crate) fn snapshot_without_genealogy(&self) -> Snapshot {
+        return Snapshot {
+            seed: Some(self.seed),
+            rng: Some(self.rng.clone()),
+            ..self.sim.snapshot_without_genealogy()
+        };
+    }
+}
+
+impl SeededSimulation {
+    /// Recreates a seeded simulation from its snapshot (snapshots of plain `Simulation`s are
+    /// continued with a generator seeded with 0); fails if the snapshot's config is invalid
+    pub fn restore(mut snapshot: Snapshot) -> Result<Self, String> {
+        let seed = snapshot.seed.unwrap_or_default();
+
+        let rng = snapshot
+            .rng
+            .take()
+            .unwrap_or_else(|| ChaCha8Rng::seed_from_u64(seed));
+
+        return Ok(Self {
+            seed,
+            rng,
+            sim: Simulation::restore(snapshot)?,
+            recorder: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(world: &World) -> Vec<na::Point2<f32>> {
+        return world
+            .animals()
+            .iter()
+            .chain(world.predators())
+            .map(Animal::position)
+            .chain(world.foods().iter().map(Food::position))
+            .collect();
+    }
+
+    mod restore {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = SimulationConfig {
+                generation_length: 50,
+                predators: 2,
+                heatmap_grid: 8,
+                ..SimulationConfig::default()
+            };
+
+            let mut sim = Simulation::from_seed(42, config).unwrap();
+
+            sim.train(None);
+            for _ in 0..20 {
+                sim.step(None);
+            }
+
+            let json = sim.snapshot().to_json();
+            let mut restored = SeededSimulation::restore(Snapshot::from_json(&json).unwrap()).unwrap();
+
+            assert_eq!(restored.seed(), 42);
+            assert_eq!(positions(restored.world()), positions(sim.world()));
+            assert_eq!(restored.simulation().genealogy().current(), sim.simulation().genealogy().current());
+
+            // ... and the restored simulation goes on exactly like the original one
+            let stats = sim.train(None);
+            let restored_stats = restored.train(None);
+
+            assert_eq!(restored_stats.avg_fitness(), stats.avg_fitness());
+            assert_eq!(restored_stats.occupancy(), stats.occupancy());
+            assert_eq!(restored_stats.feeding(), stats.feeding());
+            assert_eq!(positions(restored.world()), positions(sim.world()));
+        }
+
+        #[test]
+        fn unseeded() {
+            let mut rng = ChaCha8Rng::seed_from_u64(1);
+            let sim = Simulation::random(&mut rng);
+            let restored = Simulation::restore(sim.snapshot()).unwrap();
+
+            assert_eq!(positions(restored.world()), positions(sim.world()));
+            assert_eq!(restored.config(), sim.config());
+        }
+    }
+
+    mod from_json {
+        use super::*;
+
+        #[test]
+        fn rejects_inconsistent_snapshots() {
+            let sim = Simulation::from_seed(1, SimulationConfig::default()).unwrap();
+            let mut snapshot = sim.snapshot();
+
+            snapshot.world.animals.pop();
+
+            assert!(Snapshot::from_json(&snapshot.to_json()).is_err());
+            assert!(Snapshot::from_json("{}").is_err());
+        }
+    }
+}
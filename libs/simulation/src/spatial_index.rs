@@ -0,0 +1,179 @@
+use crate::*;
+
+/// Uniform grid over the `[0, 1)²` world, used to quickly find items (foods) near a point.
+///
+/// The grid always wraps around, as if the world was a torus: a query near the right edge of the
+/// map also looks at the cells near its left edge, and so on. For worlds with walls (see
+/// `Topology`) that just means a few more candidates to check.
+#[derive(Debug)]
+pub(crate) struct SpatialIndex {
+    /// Number of cells along each axis
+    cells: usize,
+    /// Items in each cell, row by row
+    buckets: Vec<Vec<usize>>,
+    /// Cell of each item
+    item_cells: Vec<usize>,
+}
+
+impl SpatialIndex {
+    pub(crate) fn new(cells: usize) -> Self {
+        assert!(cells > 0);
+
+        return Self {
+            cells,
+            buckets: vec![Vec::new(); cells * cells],
+            item_cells: Vec::new(),
+        };
+    }
+
+    /// Picks a resolution so that there's roughly one item per cell
+    pub(crate) fn for_items(items: usize) -> Self {
+        let cells = (items as f32).sqrt().ceil() as usize;
+        return Self::new(cells.clamp(1, 256));
+    }
+
+    /// Forgets all the items and indexes `positions` instead; item ids are positions' indices
+    pub(crate) fn rebuild(&mut self, positions: impl IntoIterator<Item = na::Point2<f32>>) {
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.item_cells.clear();
+
+        for (item, position) in positions.into_iter().enumerate() {
+            let cell = self.cell_of(position);
+
+            self.buckets[cell].push(item);
+            self.item_cells.push(cell);
+        }
+    }
+
+    /// Updates the position of an already indexed item
+    pub(crate) fn update(&mut self, item: usize, position: na::Point2<f32>) {
+        let old_cell = self.item_cells[item];
+        let new_cell = self.cell_of(position);
+
+        if old_cell == new_cell {
+            return;
+        }
+
+        self.buckets[old_cell].retain(|&other| other != item);
+        self.buckets[new_cell].push(item);
+        self.item_cells[item] = new_cell;
+    }
+
+    /// Returns, in ascending order, ids of all the items that might be within `radius` from
+    /// `center` - it's up to the caller to check the actual distance.
+    ///
+    /// Items farther away can be returned as well, but those within `radius` never get missed.
+    pub(crate) fn query(&self, center: na::Point2<f32>, radius: f32) -> Vec<usize> {
+        let columns = self.span(center.x, radius);
+        let rows = self.span(center.y, radius);
+
+        let mut items: Vec<_> = rows
+            .flat_map(|row| columns.clone().map(move |column| row * self.cells + column))
+            .flat_map(|cell| self.buckets[cell].iter().copied())
+            .collect();
+
+        // Keeping items in order makes results independent of the grid's resolution (e.g. the
+        // order in which floats are summed up)
+        items.sort_unstable();
+
+        return items;
+    }
+
+    fn cell_of(&self, position: na::Point2<f32>) -> usize {
+        return self.coord(position.y) * self.cells + self.coord(position.x);
+    }
+
+    fn coord(&self, value: f32) -> usize {
+        let coord = (value.rem_euclid(1.0) * (self.cells as f32)) as usize;
+        return coord.min(self.cells - 1);
+    }
+
+    /// Cells covering `<value - radius, value + radius>` along a single axis, wrapped around
+    fn span(&self, value: f32, radius: f32) -> impl Iterator<Item = usize> + Clone {
+        // A bit of slack for rounding errors in callers' distance checks
+        let radius = radius + 1e-5;
+        let cells = self.cells as isize;
+        let from = ((value - radius) * (cells as f32)).floor() as isize;
+        let to = ((value + radius) * (cells as f32)).floor() as isize;
+
+        let (from, to) = if to - from + 1 >= cells {
+            (0, cells - 1)
+        } else {
+            (from, to)
+        };
+
+        return (from..=to).map(move |cell| cell.rem_euclid(cells) as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn random_points(rng: &mut ChaCha8Rng, count: usize) -> Vec<na::Point2<f32>> {
+        return (0..count).map(|_| rng.gen()).collect();
+    }
+
+    mod query {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case(1, 0.05)]
+        #[test_case(4, 0.05)]
+        #[test_case(10, 0.01)]
+        #[test_case(10, 0.25)]
+        #[test_case(10, 0.7)]
+        #[test_case(33, 0.1)]
+        fn finds_everything_in_range(cells: usize, radius: f32) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let points = random_points(&mut rng, 500);
+
+            let mut index = SpatialIndex::new(cells);
+            index.rebuild(points.iter().copied());
+
+            for center in random_points(&mut rng, 50) {
+                let candidates = index.query(center, radius);
+
+                assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+
+                for (item, point) in points.iter().enumerate() {
+                    if Topology::Torus.distance(center, *point) <= radius {
+                        assert!(candidates.contains(&item), "missed {point} around {center}");
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn wraps_around() {
+            let mut index = SpatialIndex::new(10);
+
+            index.rebuild([
+                na::Point2::new(0.99, 0.99),
+                na::Point2::new(0.01, 0.01),
+                na::Point2::new(0.5, 0.5),
+            ]);
+
+            assert_eq!(index.query(na::Point2::new(0.0, 0.0), 0.02), vec![0, 1]);
+            assert_eq!(index.query(na::Point2::new(0.995, 0.005), 0.02), vec![0, 1]);
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut index = SpatialIndex::new(10);
+            index.rebuild([na::Point2::new(0.05, 0.05), na::Point2::new(0.55, 0.55)]);
+
+            index.update(0, na::Point2::new(0.52, 0.52));
+
+            assert_eq!(index.query(na::Point2::new(0.05, 0.05), 0.01), Vec::<usize>::new());
+            assert_eq!(index.query(na::Point2::new(0.53, 0.53), 0.05), vec![0, 1]);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::*;
+
+/// Summary of a generation (or, for steady-state evolution and the open-ended mode, of an
+/// interval)
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    fitness: ga::Statistics,
+    predators: Option<ga::Statistics>,
+    caught: usize,
+    starved: usize,
+    avg_energy: f32,
+    avg_energy_spent: f32,
+    born: usize,
+    immigrated: usize,
+    died_of_age: usize,
+    fittest: ga::Chromosome,
+    occupancy: Option<Heatmap>,
+    feeding: Option<Heatmap>,
+}
+
+impl Statistics {
+    pub(crate) fn new(fitness: ga::Statistics, animals: &[Animal], config: &SimulationConfig) -> Self {
+        let count = animals.len().max(1) as f32;
+
+        let caught = animals
+            .iter()
+            .filter(|animal| animal.caught)
+            .count();
+
+        let starved = animals
+            .iter()
+            .filter(|animal| animal.energy <= 0.0)
+            .count();
+
+        let avg_energy = animals.iter().map(|animal| animal.energy).sum::<f32>() / count;
+        let avg_energy_spent = animals.iter().map(|animal| animal.energy_spent).sum::<f32>() / count;
+
+        let fittest = animals
+            .iter()
+            .max_by(|a, b| a.fitness(config).total_cmp(&b.fitness(config)))
+            .expect("population is never empty")
+            .as_chromosome(config);
+
+        return Self {
+            fitness,
+            predators: None,
+            caught,
+            starved,
+            avg_energy,
+            avg_energy_spent,
+            born: 0,
+            immigrated: 0,
+            died_of_age: 0,
+            fittest,
+            occupancy: None,
+            feeding: None,
+        };
+    }
+
+    pub(crate) fn set_predators(&mut self, predators: ga::Statistics) {
+        self.predators = Some(predators);
+    }
+
+    /// In the open-ended mode dead birds are gone by the time statistics are computed, so the
+    /// deaths are counted as they happen instead
+    pub(crate) fn set_census(&mut self, census: Census) {
+        self.caught = census.caught;
+        self.starved = census.starved;
+        self.born = census.born;
+        self.immigrated = census.immigrated;
+        self.died_of_age = census.died_of_age;
+    }
+
+    pub(crate) fn set_heatmaps(&mut self, heatmaps: Option<Heatmaps>) {
+        if let Some(heatmaps) = heatmaps {
+            self.occupancy = Some(heatmaps.occupancy);
+            self.feeding = Some(heatmaps.feeding);
+        }
+    }
+
+    /// Fitness as seen by the user - i.e. foods eaten, adjusted for the energy spent, but never
+    /// the one assigned by novelty search
+    pub fn fitness(&self) -> &ga::Statistics {
+        return &self.fitness;
+    }
+
+    pub fn min_fitness(&self) -> f32 {
+        return self.fitness.min_fitness();
+    }
+
+    pub fn max_fitness(&self) -> f32 {
+        return self.fitness.max_fitness();
+    }
+
+    pub fn avg_fitness(&self) -> f32 {
+        return self.fitness.avg_fitness();
+    }
+
+    /// Fitness of predators (i.e. birds caught, adjusted for the energy spent), if there are any
+    pub fn predators(&self) -> Option<&ga::Statistics> {
+        return self.predators.as_ref();
+    }
+
+    /// Number of birds caught by predators
+    pub fn caught(&self) -> usize {
+        return self.caught;
+    }
+
+    /// Number of birds that ran out of energy
+    pub fn starved(&self) -> usize {
+        return self.starved;
+    }
+
+    /// Average energy left
+    pub fn avg_energy(&self) -> f32 {
+        return self.avg_energy;
+    }
+
+    /// Average energy consumed
+    pub fn avg_energy_spent(&self) -> f32 {
+        return self.avg_energy_spent;
+    }
+
+    /// Chromosome of the bird with the highest fitness (see `fitness()`), e.g. to save its
+    /// brain to disk
+    pub fn fittest(&self) -> &ga::Chromosome {
+        return &self.fittest;
+    }
+
+    /// Number of birds born (open-ended mode only)
+    pub fn born(&self) -> usize {
+        return self.born;
+    }
+
+    /// Number of random birds that flew in to keep the population from dying out (open-ended
+    /// mode only)
+    pub fn immigrated(&self) -> usize {
+        return self.immigrated;
+    }
+
+    /// Number of birds that died of old age (open-ended mode only)
+    pub fn died_of_age(&self) -> usize {
+        return self.died_of_age;
+    }
+
+    /// Where birds have been during the generation (see `Simulation::occupancy()`), if
+    /// `SimulationConfig::heatmap_grid` is set
+    pub fn occupancy(&self) -> Option<&Heatmap> {
+        return self.occupancy.as_ref();
+    }
+
+    /// Where birds have eaten during the generation (see `Simulation::feeding()`), if
+    /// `SimulationConfig::heatmap_grid` is set
+    pub fn feeding(&self) -> Option<&Heatmap> {
+        return self.feeding.as_ref();
+    }
+}
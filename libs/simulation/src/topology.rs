@@ -0,0 +1,179 @@
+use std::f32::consts::PI;
+
+use crate::*;
+
+/// Shape of the `[0, 1]²` world, which determines how birds move around its borders and how far
+/// apart things are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Topology {
+    /// Borders wrap around: a bird flying off the right edge reappears on the left one - and it
+    /// can see (and eat) food on the other side of the edge
+    #[default]
+    Torus,
+
+    /// Borders are walls: a bird flying into one slides along it
+    Walls,
+
+    /// Borders are walls: a bird flying into one bounces off it like a billiard ball
+    Bounce,
+}
+
+impl Topology {
+    /// Shortest vector pointing from `from` to `to`
+    pub fn displacement(self, from: na::Point2<f32>, to: na::Point2<f32>) -> na::Vector2<f32> {
+        let vec = to - from;
+
+        return match self {
+            Self::Torus => vec.map(|d| {
+                if d > 0.5 {
+                    return d - 1.0;
+                }
+                if d < -0.5 {
+                    return d + 1.0;
+                }
+                return d;
+            }),
+
+            Self::Walls | Self::Bounce => vec,
+        };
+    }
+
+    pub fn distance(self, a: na::Point2<f32>, b: na::Point2<f32>) -> f32 {
+        return self.displacement(a, b).norm();
+    }
+
+    /// Brings a bird that's just moved to `position` back into the world, adjusting its
+    /// `rotation` if needed
+    pub(crate) fn constrain(
+        self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+    ) -> (na::Point2<f32>, na::Rotation2<f32>) {
+        match self {
+            Self::Torus => {
+                let position = position.map(|coord| na::wrap(coord, 0.0, 1.0));
+                return (position, rotation);
+            }
+
+            Self::Walls => {
+                let position = position.map(|coord| coord.clamp(0.0, 1.0));
+                return (position, rotation);
+            }
+
+            Self::Bounce => {
+                let bounces_x = !(0.0..=1.0).contains(&position.x);
+                let bounces_y = !(0.0..=1.0).contains(&position.y);
+
+                if !bounces_x && !bounces_y {
+                    return (position, rotation);
+                }
+
+                let mut angle = trig::angle(&rotation);
+                let mut position = position;
+
+                if bounces_x {
+                    position.x = reflect(position.x);
+                    angle = PI - angle;
+                }
+
+                if bounces_y {
+                    position.y = reflect(position.y);
+                    angle = -angle;
+                }
+
+                return (position, trig::rotation(angle));
+            }
+        }
+    }
+}
+
+/// Mirrors a coordinate that's slightly out of `[0, 1]` back into it
+fn reflect(coord: f32) -> f32 {
+    let coord = if coord < 0.0 { -coord } else { 2.0 - coord };
+    return coord.clamp(0.0, 1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn point(x: f32, y: f32) -> na::Point2<f32> {
+        return na::Point2::new(x, y);
+    }
+
+    mod displacement {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case(Topology::Torus, 0.9, 0.5, 0.1, 0.5, 0.2, 0.0; "torus across the right edge")]
+        #[test_case(Topology::Torus, 0.1, 0.5, 0.9, 0.5, -0.2, 0.0; "torus across the left edge")]
+        #[test_case(Topology::Torus, 0.5, 0.95, 0.5, 0.05, 0.0, 0.1; "torus across the top edge")]
+        #[test_case(Topology::Torus, 0.95, 0.95, 0.05, 0.05, 0.1, 0.1; "torus across the corner")]
+        #[test_case(Topology::Torus, 0.2, 0.2, 0.4, 0.6, 0.2, 0.4; "torus inside")]
+        #[test_case(Topology::Walls, 0.9, 0.5, 0.1, 0.5, -0.8, 0.0; "walls")]
+        #[test_case(Topology::Bounce, 0.95, 0.95, 0.05, 0.05, -0.9, -0.9; "bounce")]
+        fn test(topology: Topology, x1: f32, y1: f32, x2: f32, y2: f32, dx: f32, dy: f32) {
+            let actual = topology.displacement(point(x1, y1), point(x2, y2));
+
+            assert_relative_eq!(actual.x, dx, epsilon = 1e-6);
+            assert_relative_eq!(actual.y, dy, epsilon = 1e-6);
+        }
+    }
+
+    mod constrain {
+        use super::*;
+        use std::f32::consts::FRAC_PI_4;
+
+        #[test]
+        fn torus() {
+            let (position, rotation) = Topology::Torus.constrain(point(1.02, -0.01), trig::rotation(FRAC_PI_4));
+
+            assert_relative_eq!(position.x, 0.02, epsilon = 1e-6);
+            assert_relative_eq!(position.y, 0.99, epsilon = 1e-6);
+            assert_relative_eq!(trig::angle(&rotation), FRAC_PI_4);
+        }
+
+        #[test]
+        fn walls() {
+            let (position, rotation) = Topology::Walls.constrain(point(1.02, -0.01), trig::rotation(FRAC_PI_4));
+
+            assert_eq!(position, point(1.0, 0.0));
+            assert_relative_eq!(trig::angle(&rotation), FRAC_PI_4);
+        }
+
+        #[test]
+        fn bounce_off_vertical_wall() {
+            let (position, rotation) = Topology::Bounce.constrain(point(1.02, 0.5), trig::rotation(FRAC_PI_4));
+
+            assert_relative_eq!(position.x, 0.98, epsilon = 1e-6);
+            assert_relative_eq!(position.y, 0.5);
+            assert_relative_eq!(trig::angle(&rotation), 3.0 * FRAC_PI_4, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn bounce_off_horizontal_wall() {
+            let (position, rotation) = Topology::Bounce.constrain(point(0.5, -0.01), trig::rotation(-FRAC_PI_4));
+
+            assert_relative_eq!(position.x, 0.5);
+            assert_relative_eq!(position.y, 0.01, epsilon = 1e-6);
+            assert_relative_eq!(trig::angle(&rotation), FRAC_PI_4, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn inside() {
+            for topology in [Topology::Torus, Topology::Walls, Topology::Bounce] {
+                let (position, rotation) = topology.constrain(point(0.3, 0.7), trig::rotation(1.0));
+
+                assert_eq!(position, point(0.3, 0.7));
+                assert_relative_eq!(trig::angle(&rotation), 1.0, epsilon = 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Trigonometry used by the simulation.
+//!
+//! `f32::sin()` & co. are delegated to the platform's math library, which is allowed to round
+//! differently on different targets (e.g. glibc vs the wasm runtime); these functions use the
+//! pure-Rust `libm` instead, so that seeded simulations don't depend on how a target rounds them.
+
+use std::f32::consts::TAU;
+
+use crate::*;
+
+/// Same as `na::Rotation2::new(angle)`
+pub(crate) fn rotation(angle: f32) -> na::Rotation2<f32> {
+    let (sin, cos) = libm::sincosf(angle);
+    return na::Rotation2::from_matrix_unchecked(na::Matrix2::new(cos, -sin, sin, cos));
+}
+
+/// Same as `rotation.angle()`
+pub(crate) fn angle(rotation: &na::Rotation2<f32>) -> f32 {
+    let matrix = rotation.matrix();
+    return libm::atan2f(matrix[(1, 0)], matrix[(0, 0)]);
+}
+
+/// Angle between the X axis and `vec`
+pub(crate) fn direction(vec: &na::Vector2<f32>) -> f32 {
+    return libm::atan2f(vec.y, vec.x);
+}
+
+pub(crate) fn random_rotation(rng: &mut dyn RngCore) -> na::Rotation2<f32> {
+    return rotation(rng.gen::<f32>() * TAU);
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    mod rotation {
+        use super::*;
+
+        #[test]
+        fn test() {
+            for radians in [0.0, 0.5, -1.0, PI / 2.0, 3.0] {
+                let expected = na::Rotation2::new(radians);
+                let actual = rotation(radians);
+
+                assert_relative_eq!(actual.matrix(), expected.matrix(), epsilon = 1e-6);
+                assert_relative_eq!(angle(&actual), expected.angle(), epsilon = 1e-6);
+            }
+        }
+    }
+
+    mod direction {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_relative_eq!(direction(&na::Vector2::new(1.0, 0.0)), 0.0);
+            assert_relative_eq!(direction(&na::Vector2::new(0.0, 1.0)), PI / 2.0);
+            assert_relative_eq!(direction(&na::Vector2::new(-1.0, 0.0)), PI);
+        }
+    }
+}
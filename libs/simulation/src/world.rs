@@ -1,22 +1,34 @@
-use crate::{Animal, Food, RngCore};
+use crate::{food_spawner, na, Animal, Food, Obstacle, Rng, RngCore, SimulationConfig, Species};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    pub(crate) predators: Vec<Animal>,
+    pub(crate) obstacles: Vec<Obstacle>,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40)
-            .map(|_| Animal::random(rng))
+    pub fn random(rng: &mut dyn RngCore, config: &SimulationConfig) -> Self {
+        let animals = (0..config.animals)
+            .map(|_| Animal::random(rng, config))
             .collect();
 
-        let foods = (0..60)
-            .map(|_| Food::random(rng))
+        let foods = (0..config.foods)
+            .map(|_| food_spawner::spawn(rng, config))
             .collect();
 
-        return Self { animals, foods };
+        let predators = (0..config.predators)
+            .map(|_| Animal::random_of(Species::Predator, rng, config))
+            .collect();
+
+        return Self {
+            animals,
+            foods,
+            predators,
+            obstacles: config.obstacles.clone(),
+        };
     }
     pub fn animals(&self) -> &[Animal] {
         return &self.animals;
@@ -24,4 +36,37 @@ impl World {
     pub fn foods(&self) -> &[Food] {
         return &self.foods;
     }
+    pub fn predators(&self) -> &[Animal] {
+        return &self.predators;
+    }
+    pub fn obstacles(&self) -> &[Obstacle] {
+        return &self.obstacles;
+    }
+}
+
+/// Returns a random position that doesn't lie inside any of `obstacles`, which mustn't cover
+/// the entire world (see `has_room()`)
+pub(crate) fn random_position(rng: &mut dyn RngCore, obstacles: &[Obstacle]) -> na::Point2<f32> {
+    loop {
+        let position = rng.gen();
+
+        if !obstacles.iter().any(|obstacle| obstacle.contains(position)) {
+            return position;
+        }
+    }
+}
+
+/// Whether `obstacles` leave any room for spawning things, judging by a grid of sample points
+/// spread over the world
+pub(crate) fn has_room(obstacles: &[Obstacle]) -> bool {
+    const SAMPLES: usize = 100;
+
+    return (0..SAMPLES * SAMPLES)
+        .map(|idx| {
+            let x = (idx % SAMPLES) as f32 + 0.5;
+            let y = (idx / SAMPLES) as f32 + 0.5;
+
+            return na::Point2::new(x, y) / SAMPLES as f32;
+        })
+        .any(|point| !obstacles.iter().any(|obstacle| obstacle.contains(point)));
 }
@@ -1,9 +1,101 @@
 import * as sim from "lib-simulation-wasm";
 
-const simulation = new sim.Simulation();
+let simulation = new sim.Simulation({ heatmap_grid: 40 });
+
+// Statistics of the last generation that has ended, along with its heatmaps (if enabled)
+let last_result = null;
+
 document.getElementById('train').onclick = function () {
-    const result = simulation.train();
-    set_result(result.min, result.max, result.avg);
+    set_result(simulation.train());
+};
+
+function download(contents, name) {
+    const blob = new Blob([contents], { type: 'application/json' });
+    const link = document.createElement('a');
+
+    link.href = URL.createObjectURL(blob);
+    link.download = name;
+    link.click();
+
+    URL.revokeObjectURL(link.href);
+}
+
+// Saves the whole world as a JSON file, so that it can be shared and imported later
+document.getElementById('export').onclick = function () {
+    download(simulation.export(), `shorelark-${simulation.seed()}.json`);
+};
+
+const import_file = document.getElementById('import_file');
+document.getElementById('import').onclick = () => import_file.click();
+
+import_file.onchange = async function () {
+    const file = import_file.files[0];
+    if (!file) return;
+
+    try {
+        simulation = sim.Simulation.import(await file.text());
+        last_result = null;
+    } catch (err) {
+        alert(`Couldn't import the simulation: ${err}`);
+    }
+
+    import_file.value = '';
+};
+
+const record = document.getElementById('record');
+
+record.onclick = function () {
+    if (simulation.isRecording()) {
+        download(simulation.stopRecording(), `shorelark-${simulation.seed()}-replay.json`);
+        record.textContent = 'Record';
+    } else {
+        simulation.startRecording();
+        record.textContent = 'Stop recording';
+    }
+};
+
+// While a replay is loaded, it's drawn instead of the live simulation
+let replay = null;
+
+const replay_file = document.getElementById('replay_file');
+const replay_controls = document.getElementById('replay_controls');
+const replay_position = document.getElementById('replay_position');
+
+document.getElementById('replay').onclick = () => replay_file.click();
+
+replay_file.onchange = async function () {
+    const file = replay_file.files[0];
+    if (!file) return;
+
+    try {
+        replay = new sim.Replay(await file.text());
+        last_result = null;
+        replay_position.max = replay.len();
+        replay_controls.hidden = false;
+    } catch (err) {
+        alert(`Couldn't load the replay: ${err}`);
+    }
+
+    replay_file.value = '';
+};
+
+replay_position.oninput = () => replay.seek(Number(replay_position.value));
+
+document.getElementById('replay_exit').onclick = function () {
+    replay = null;
+    last_result = null;
+    replay_controls.hidden = true;
+};
+
+// Overlays where birds have been (or eaten) during the last generation that has ended
+const heatmaps = ['off', 'occupancy', 'feeding'];
+let heatmap = 0;
+
+const heatmap_button = document.getElementById('heatmap');
+
+heatmap_button.onclick = function () {
+    heatmap = (heatmap + 1) % heatmaps.length;
+    heatmap_button.textContent = `Heatmap: ${heatmaps[heatmap]}`;
 };
 
 const stats_min = document.getElementById('stats_min');
@@ -22,7 +114,7 @@ viewport.style.height = viewport_height + 'px';
 const context = viewport.getContext('2d');
 context.fillStyle = 'rgb(0, 0, 0)';
 
-CanvasRenderingContext2D.prototype.draw_triangle = function (x, y, size, rotation) {
+CanvasRenderingContext2D.prototype.draw_triangle = function (x, y, size, rotation, color) {
     this.beginPath();
 
     const multiplier = 1.5;
@@ -39,7 +131,7 @@ CanvasRenderingContext2D.prototype.draw_triangle = function (x, y, size, rotatio
     );
     this.lineTo(origin_x, origin_y);
 
-    this.fillStyle = 'rgb(255, 255, 255)'; // A nice white color
+    this.fillStyle = color;
     this.fill();
     // this.stroke();
 
@@ -52,25 +144,103 @@ CanvasRenderingContext2D.prototype.draw_circle = function (x, y, radius) {
     this.fill();
 }
 
-function set_result(min, max, avg) {
-    stats_min.textContent = min;
-    stats_max.textContent = max;
-    stats_avg.textContent = avg.toFixed(2);
+CanvasRenderingContext2D.prototype.draw_obstacle = function (obstacle, width, height) {
+    this.fillStyle = 'rgb(96, 96, 96)';
+    this.strokeStyle = 'rgb(96, 96, 96)';
+    this.lineWidth = 2 * viewport_scale;
+
+    switch (obstacle.shape) {
+        case 'circle':
+            this.beginPath();
+            this.arc(obstacle.center[0] * width, obstacle.center[1] * height, obstacle.radius * width, 0, 2.0 * Math.PI);
+            this.fill();
+            break;
+
+        case 'rect':
+            this.fillRect(
+                obstacle.min[0] * width,
+                obstacle.min[1] * height,
+                (obstacle.max[0] - obstacle.min[0]) * width,
+                (obstacle.max[1] - obstacle.min[1]) * height,
+            );
+            break;
+
+        case 'wall':
+            this.beginPath();
+            this.moveTo(obstacle.from[0] * width, obstacle.from[1] * height);
+            this.lineTo(obstacle.to[0] * width, obstacle.to[1] * height);
+            this.stroke();
+            break;
+    }
+}
+
+CanvasRenderingContext2D.prototype.draw_heatmap = function (values, width, height) {
+    const size = Math.round(Math.sqrt(values.length));
+    const max = values.reduce((a, b) => Math.max(a, b), 0);
+    if (max <= 0) return;
+
+    const [cell_width, cell_height] = [width / size, height / size];
+
+    for (let y = 0; y < size; y++) {
+        for (let x = 0; x < size; x++) {
+            this.fillStyle = `rgba(255, 96, 0, ${0.6 * values[y * size + x] / max})`;
+            this.fillRect(x * cell_width, y * cell_height, cell_width, cell_height);
+        }
+    }
+}
+
+function set_result(result) {
+    last_result = result;
+
+    stats_min.textContent = result.min;
+    stats_max.textContent = result.max;
+    stats_avg.textContent = result.avg.toFixed(2);
 }
 
 function redraw() {
     context.clearRect(0, 0, viewport_width, viewport_height);
-    const result = simulation.step();
-    if (!!result) set_result(result.min, result.max, result.avg);
 
-    const world = simulation.world();
+    let world;
+
+    if (replay) {
+        const result = replay.stepForward();
+        if (!!result) set_result(result);
+
+        replay_position.value = replay.position();
+        world = replay.world();
+    } else {
+        const result = simulation.step();
+        if (!!result) set_result(result);
+
+        world = simulation.world();
+    }
+
+    if (last_result && heatmaps[heatmap] !== 'off') {
+        const values = heatmaps[heatmap] === 'occupancy' ? last_result.occupancy : last_result.feeding;
+        if (values) context.draw_heatmap(values, viewport_width, viewport_height);
+    }
+
+    for (const obstacle of world.obstacles) {
+        context.draw_obstacle(obstacle, viewport_width, viewport_height);
+    }
 
     for (const food of world.foods) {
-        context.draw_circle(food.x * viewport_width, food.y * viewport_height, (0.01 / 2.0) * viewport_width);
+        context.draw_circle(food.x * viewport_width, food.y * viewport_height, (0.01 / 2.0) * Math.sqrt(food.nutrition) * viewport_width);
     }
 
     for (const animal of world.animals) {
-        context.draw_triangle(animal.x * viewport_width, animal.y * viewport_height, 0.01 * viewport_width, animal.rotation);
+        if (!animal.alive) continue;
+
+        // Predators are drawn a bit larger, so that they stand out
+        const size = (animal.species === 'predator' ? 1.5 : 1.0) * animal.radius;
+        let color = animal.species === 'predator' ? 'rgb(255, 64, 64)' : 'rgb(255, 255, 255)';
+
+        // Evolved bodies come with their own colors
+        if (animal.color != null) {
+            color = `hsl(${animal.color * 360}, 100%, ${animal.species === 'predator' ? 50 : 75}%)`;
+        }
+
+        context.draw_triangle(animal.x * viewport_width, animal.y * viewport_height, size * viewport_width, animal.rotation, color);
     }
 
     requestAnimationFrame(redraw);